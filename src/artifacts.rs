@@ -0,0 +1,196 @@
+use home::cargo_home;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::*;
+
+/// An executable reported by cargo in a `compiler-artifact` build message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Executable {
+    pub target_name: String,
+    pub kinds: Vec<String>,
+    pub path: PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct BuildMessage {
+    reason: String,
+    target: Option<BuildTarget>,
+    executable: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BuildTarget {
+    name: String,
+    #[serde(default)]
+    kind: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct CargoConfig {
+    #[serde(default)]
+    build: BuildConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct BuildConfig {
+    target_dir: Option<String>,
+}
+
+/// Resolves the target directory cargo uses when invoked from `cargo_dir`.
+pub fn resolve_target_dir(cargo_dir: &Path) -> PathBuf {
+    let env_override = [TARGET_DIR_ENV, BUILD_TARGET_DIR_ENV]
+        .iter()
+        .filter_map(env::var_os)
+        .find(|path| !path.is_empty())
+        .map(PathBuf::from);
+    resolve_target_dir_from(cargo_dir, env_override, cargo_home().ok().as_deref())
+}
+
+/// Resolves the target directory the way cargo does: an environment override
+/// first, then `build.target-dir` from the closest `.cargo/config.toml` above
+/// `cargo_dir` or in `$CARGO_HOME`, then `target/` in `cargo_dir`.
+///
+/// Relative environment paths are taken from `cargo_dir`; relative config
+/// paths from the parent of the directory holding the config file.
+pub fn resolve_target_dir_from(
+    cargo_dir: &Path,
+    env_override: Option<PathBuf>,
+    cargo_home: Option<&Path>,
+) -> PathBuf {
+    if let Some(target_dir) = env_override {
+        return cargo_dir.join(target_dir);
+    }
+
+    let absolute_dir = fs::canonicalize(cargo_dir).unwrap_or_else(|_| cargo_dir.to_path_buf());
+    let config_dirs = absolute_dir
+        .ancestors()
+        .map(|dir| dir.join(CARGO_CONFIG_DIR))
+        .chain(cargo_home.map(Path::to_path_buf));
+
+    for config_dir in config_dirs {
+        if let Some(target_dir) = configured_target_dir(&config_dir) {
+            let base = config_dir.parent().unwrap_or(&config_dir);
+            return base.join(target_dir);
+        }
+    }
+
+    cargo_dir.join(TARGET_DIR)
+}
+
+fn configured_target_dir(config_dir: &Path) -> Option<String> {
+    CARGO_CONFIG_FILES.iter().find_map(|file| {
+        let contents = fs::read_to_string(config_dir.join(file)).ok()?;
+        let config = toml::from_str::<CargoConfig>(&contents).ok()?;
+        config.build.target_dir
+    })
+}
+
+/// The output directory name for a cargo profile: `dev` and `test` build into
+/// `debug`, `bench` into `release`, and custom profiles into their own name.
+pub fn profile_dir_name(profile: &str) -> &str {
+    match profile {
+        DEV_PROFILE | TEST_PROFILE => DEBUG_DIR,
+        BENCH_PROFILE => RELEASE_DIR,
+        other => other,
+    }
+}
+
+/// The cargo arguments that select `profile`, using the shorthand cargo
+/// prints for the built-in profiles.
+pub fn profile_args(profile: &str) -> Vec<&str> {
+    match profile {
+        DEV_PROFILE => vec![],
+        RELEASE_PROFILE => vec!["--release"],
+        other => vec!["--profile", other],
+    }
+}
+
+/// A cargo subcommand followed by the arguments that select `profile`.
+pub fn profile_command<'a>(command: &'a str, profile: Option<&'a str>) -> Vec<&'a str> {
+    let mut args = vec![command];
+    args.extend(profile.map(profile_args).unwrap_or_default());
+    args
+}
+
+/// The directory a profile's artifacts are written to: `<target>/<profile>/`
+/// for the host, `<target>/<triple>/<profile>/` when cross-compiling.
+pub fn profile_dir(target_dir: &Path, triple: Option<&str>, profile: &str) -> PathBuf {
+    let mut dir = target_dir.to_path_buf();
+    if let Some(triple) = triple {
+        dir.push(triple);
+    }
+    dir.join(profile_dir_name(profile))
+}
+
+/// The executable file extension for a target triple, or for the host when
+/// no triple is given.
+pub fn binary_extension(triple: Option<&str>) -> &'static str {
+    match triple {
+        None => BINARY_EXTENSION,
+        Some(triple) if triple.contains("-windows") => ".exe",
+        Some(triple) if triple.starts_with("wasm32") || triple.starts_with("wasm64") => ".wasm",
+        Some(_) => "",
+    }
+}
+
+pub fn binary_path(
+    target_dir: &Path,
+    triple: Option<&str>,
+    profile: &str,
+    binary_name: &str,
+) -> PathBuf {
+    profile_dir(target_dir, triple, profile).join(format!(
+        "{}{}",
+        binary_name,
+        binary_extension(triple)
+    ))
+}
+
+pub fn example_path(
+    target_dir: &Path,
+    triple: Option<&str>,
+    profile: &str,
+    example_name: &str,
+) -> PathBuf {
+    profile_dir(target_dir, triple, profile)
+        .join(EXAMPLES_DIR)
+        .join(format!("{}{}", example_name, binary_extension(triple)))
+}
+
+/// Parses one line of cargo's JSON build output. Returns `None` for lines that
+/// aren't cargo messages, and the executable (if any) for those that are.
+pub fn parse_build_message(line: &str) -> Option<Option<Executable>> {
+    let message = serde_json::from_str::<BuildMessage>(line).ok()?;
+    if message.reason != COMPILER_ARTIFACT_REASON {
+        return Some(None);
+    }
+
+    let executable = message
+        .executable
+        .zip(message.target)
+        .map(|(path, target)| Executable {
+            target_name: target.name,
+            kinds: target.kind,
+            path,
+        });
+    Some(executable)
+}
+
+/// Picks the executable built for the `kind` target (e.g. `bin`) named `name`.
+pub fn find_executable<'a>(
+    executables: &'a [Executable],
+    kind: &str,
+    name: &str,
+) -> Option<&'a Path> {
+    executables
+        .iter()
+        .rev()
+        .find(|executable| {
+            executable.target_name == name && executable.kinds.iter().any(|k| k == kind)
+        })
+        .map(|executable| executable.path.as_path())
+}
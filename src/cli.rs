@@ -1,20 +1,103 @@
-use anyhow::{Result, anyhow};
 use std::env;
+use std::fmt;
 
-use crate::helpers::{print_banner, print_help};
+use crate::error::{Result, RustrError};
+use crate::features::FeatureArgs;
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
 pub struct CliArgs {
+    pub toolchain: Option<String>,
     pub test: bool,
     pub build: bool,
     pub release: bool,
     pub release_bin: Option<Option<String>>,
+    pub debug_run: bool,
+    pub dry_run: bool,
+    pub reindex: bool,
+    pub list: bool,
+    pub json: bool,
+    pub bin: Option<String>,
+    pub example: Option<String>,
+    pub features: FeatureArgs,
+    pub profile: Option<String>,
+    pub target_triple: Option<String>,
+    pub extra_cargo_args: Vec<String>,
     pub project: Option<String>,
     pub project_name: Option<String>,
     pub project_args: Vec<String>,
 }
 
-pub fn parse_args_from<I, S>(iter: I) -> Result<CliArgs>
+/// A task run against the project instead of building and running it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    Test,
+    Build,
+    Release,
+    /// Build in release mode and copy the binary, to the given directory or
+    /// `~/bin`.
+    ReleaseBin(Option<String>),
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flag = match self {
+            Action::Test => "--test",
+            Action::Build => "--build",
+            Action::Release => "--release",
+            Action::ReleaseBin(_) => "--release-bin",
+        };
+        write!(f, "{}", flag)
+    }
+}
+
+/// What the command line asks rustr to do.
+// Parsed once per run, so boxing the arguments would buy nothing
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseOutcome {
+    Run(CliArgs),
+    Help,
+    Version,
+}
+
+impl CliArgs {
+    /// The actions to run, in pipeline order: test, build, release, then
+    /// release-bin. Empty when the project should be built and run.
+    pub fn actions(&self) -> Vec<Action> {
+        let mut actions = Vec::new();
+        if self.test {
+            actions.push(Action::Test);
+        }
+        if self.build {
+            actions.push(Action::Build);
+        }
+        if self.release {
+            actions.push(Action::Release);
+        }
+        if let Some(dest) = &self.release_bin {
+            actions.push(Action::ReleaseBin(dest.clone()));
+        }
+        actions
+    }
+
+    /// Cargo flags that apply to every cargo command rustr runs.
+    pub fn cargo_flags(&self) -> Vec<String> {
+        let mut flags = Vec::new();
+        if let Some(bin) = &self.bin {
+            flags.extend(["--bin".into(), bin.clone()]);
+        }
+        if let Some(example) = &self.example {
+            flags.extend(["--example".into(), example.clone()]);
+        }
+        if let Some(triple) = &self.target_triple {
+            flags.extend(["--target".into(), triple.clone()]);
+        }
+        flags.extend(self.extra_cargo_args.iter().cloned());
+        flags
+    }
+}
+
+pub fn parse_args_from<I, S>(iter: I) -> Result<ParseOutcome>
 where
     I: IntoIterator<Item = S>,
     S: Into<String>,
@@ -24,6 +107,7 @@ where
     let mut parsed_args = CliArgs::default();
     let mut project_args = Vec::<String>::new();
     let mut stop_option_parsing = false;
+    let mut json_positions = Vec::new();
 
     while let Some(token) = tokens.next() {
         if stop_option_parsing {
@@ -36,18 +120,36 @@ where
             continue;
         }
 
-        match token.as_str() {
-            "-h" | "--help" => {
-                print_help();
-                std::process::exit(0);
-            }
-            "-V" | "--version" => {
-                print_banner();
-                std::process::exit(0);
+        // A `+toolchain` before the project name is forwarded to cargo, as
+        // with rustup's proxies
+        if let Some(name) = token.strip_prefix('+')
+            && parsed_args.project.is_none()
+            && project_args.is_empty()
+        {
+            if name.is_empty() {
+                return Err(usage("Missing toolchain name after +"));
             }
+            parsed_args.toolchain = Some(name.into());
+            continue;
+        }
+
+        match token.as_str() {
+            "-h" | "--help" => return Ok(ParseOutcome::Help),
+            "-V" | "--version" => return Ok(ParseOutcome::Version),
             "--test" => parsed_args.test = true,
             "--build" => parsed_args.build = true,
             "--release" => parsed_args.release = true,
+            "--debug-run" => parsed_args.debug_run = true,
+            "-n" | "--dry-run" => parsed_args.dry_run = true,
+            "--reindex" => parsed_args.reindex = true,
+            "--list" => parsed_args.list = true,
+            // Only ours with --list or --dry-run, so keep its place until then
+            "--json" => {
+                json_positions.push(project_args.len());
+                project_args.push(token);
+            }
+            "--all-features" => parsed_args.features.all_features = true,
+            "--no-default-features" => parsed_args.features.no_default_features = true,
 
             arg if arg == "--release-bin" || arg.starts_with("--release-bin=") => {
                 if let Some(dest) = arg.strip_prefix("--release-bin=") {
@@ -56,47 +158,169 @@ where
                     } else {
                         Some(Some(dest.into()))
                     };
-                } else if let Some(next) = tokens.peek() {
-                    if !next.starts_with("--") {
-                        parsed_args.release_bin = Some(Some(tokens.next().unwrap()));
-                    } else {
-                        parsed_args.release_bin = Some(None);
-                    }
                 } else {
-                    parsed_args.release_bin = Some(None);
+                    // A following flag, short or long, is never the destination
+                    let dest = tokens.next_if(|next| !next.starts_with('-'));
+                    parsed_args.release_bin = Some(dest);
                 }
             }
 
             arg if arg == "--project" || arg.starts_with("--project=") => {
-                if let Some(name) = arg.strip_prefix("--project=") {
-                    if name.is_empty() {
-                        return Err(anyhow!("Missing project name after --project"));
-                    }
-                    parsed_args.project = Some(name.into());
-                } else if let Some(name) = tokens.next() {
-                    parsed_args.project = Some(name);
-                } else {
-                    return Err(anyhow!("Missing project name after --project"));
-                }
+                parsed_args.project = Some(required_value(
+                    arg,
+                    "--project",
+                    "project name",
+                    &mut tokens,
+                )?);
+            }
+
+            arg if arg == "--bin" || arg.starts_with("--bin=") => {
+                parsed_args.bin = Some(required_value(arg, "--bin", "binary name", &mut tokens)?);
+            }
+
+            arg if arg == "--features" || arg.starts_with("--features=") => {
+                let features = required_value(arg, "--features", "feature name", &mut tokens)?;
+                parsed_args.features.add_features(&features);
+            }
+
+            arg @ "-F" => {
+                let features = required_value(arg, "-F", "feature name", &mut tokens)?;
+                parsed_args.features.add_features(&features);
+            }
+
+            arg if arg == "--profile" || arg.starts_with("--profile=") => {
+                parsed_args.profile = Some(required_value(
+                    arg,
+                    "--profile",
+                    "profile name",
+                    &mut tokens,
+                )?);
+            }
+
+            arg if arg == "--target" || arg.starts_with("--target=") => {
+                parsed_args.target_triple = Some(required_value(
+                    arg,
+                    "--target",
+                    "target triple",
+                    &mut tokens,
+                )?);
+            }
+
+            arg if arg == "--cargo-arg" || arg.starts_with("--cargo-arg=") => {
+                let cargo_arg = required_value(arg, "--cargo-arg", "cargo argument", &mut tokens)?;
+                parsed_args.extra_cargo_args.push(cargo_arg);
+            }
+
+            arg if arg == "--example" || arg.starts_with("--example=") => {
+                parsed_args.example = Some(required_value(
+                    arg,
+                    "--example",
+                    "example name",
+                    &mut tokens,
+                )?);
             }
 
             other => project_args.push(other.to_owned()),
         }
     }
 
-    if parsed_args.project.is_none() {
-        if let Some(first) = project_args.first() {
-            if !first.starts_with("--") {
-                parsed_args.project_name = Some(project_args.remove(0));
-            }
+    // Otherwise --json goes to the program like any other unknown flag
+    if parsed_args.list || parsed_args.dry_run {
+        parsed_args.json = !json_positions.is_empty();
+        for position in json_positions.into_iter().rev() {
+            project_args.remove(position);
         }
     }
 
+    if parsed_args.project.is_none()
+        && let Some(first) = project_args.first()
+        && !first.starts_with("--")
+    {
+        parsed_args.project_name = Some(project_args.remove(0));
+    }
+
     parsed_args.project_args = project_args;
 
-    Ok(parsed_args)
+    if parsed_args.bin.is_some() && parsed_args.example.is_some() {
+        return Err(usage("--bin and --example can't be used together"));
+    }
+
+    check_action_conflicts(&parsed_args)?;
+
+    Ok(ParseOutcome::Run(parsed_args))
+}
+
+/// Rejects action flags that would repeat or contradict each other, rather
+/// than quietly dropping one of them.
+fn check_action_conflicts(args: &CliArgs) -> Result<()> {
+    let actions = args.actions();
+
+    if args.release && args.release_bin.is_some() {
+        return Err(usage(
+            "--release and --release-bin can't be used together: --release-bin already makes a release build",
+        ));
+    }
+
+    if let Some(profile) = &args.profile
+        && args.build
+        && args.release
+    {
+        return Err(usage(format!(
+            "--build and --release can't be used together with --profile: both would build the '{}' profile",
+            profile
+        )));
+    }
+
+    if let Some(profile) = &args.profile
+        && args.debug_run
+    {
+        return Err(usage(format!(
+            "--debug-run and --profile can't be used together: --profile {} already picks the profile to run",
+            profile
+        )));
+    }
+
+    if let Some(action) = actions.first() {
+        if args.debug_run {
+            return Err(usage(format!(
+                "--debug-run and {} can't be used together: --debug-run only applies when running the project",
+                action
+            )));
+        }
+        if args.list {
+            return Err(usage(format!(
+                "--list and {} can't be used together",
+                action
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+fn usage(message: impl Into<String>) -> RustrError {
+    RustrError::Usage(message.into())
+}
+
+/// Takes the value of a flag that requires one, given either as `--flag=value`
+/// or as the next token (even one that looks like a flag itself).
+fn required_value<I>(arg: &str, flag: &str, what: &str, tokens: &mut I) -> Result<String>
+where
+    I: Iterator<Item = String>,
+{
+    let value = match arg
+        .strip_prefix(flag)
+        .and_then(|rest| rest.strip_prefix('='))
+    {
+        Some(value) => Some(value.to_owned()),
+        None => tokens.next(),
+    };
+
+    value
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| usage(format!("Missing {} after {}", what, flag)))
 }
 
-pub fn parse_args() -> Result<CliArgs> {
+pub fn parse_args() -> Result<ParseOutcome> {
     parse_args_from(env::args().skip(1))
 }
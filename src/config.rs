@@ -0,0 +1,157 @@
+use home::home_dir;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::constants::*;
+use crate::discovery::ProjectSearch;
+use crate::error::{Result, RustrError};
+use crate::features::FeatureArgs;
+use crate::index::index_file_path;
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    pub search_paths: Vec<String>,
+    pub search_depth: Option<usize>,
+    pub prefix_match: bool,
+    /// Seconds to wait after forwarding a signal to cargo before killing it.
+    pub grace_period: Option<u64>,
+    pub projects: BTreeMap<String, ProjectConfig>,
+}
+
+/// Per-project defaults, keyed by package name under `[projects.<name>]`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+    /// Run the dev profile binary instead of the release one.
+    pub debug_run: bool,
+}
+
+impl ProjectConfig {
+    pub fn feature_args(&self) -> FeatureArgs {
+        let mut args = FeatureArgs {
+            all_features: self.all_features,
+            no_default_features: self.no_default_features,
+            ..FeatureArgs::default()
+        };
+        for feature in &self.features {
+            args.add_features(feature);
+        }
+        args
+    }
+}
+
+impl Config {
+    pub fn load() -> Result<Self> {
+        match config_file_path() {
+            Some(path) if path.exists() => Self::from_file(&path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|error| {
+            RustrError::io(
+                format!("Could not read config file {}", path.display()),
+                error,
+            )
+        })?;
+        Self::parse(&contents).map_err(|error| RustrError::Config {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn grace_period(&self) -> Duration {
+        Duration::from_secs(self.grace_period.unwrap_or(DEFAULT_GRACE_PERIOD_SECS))
+    }
+
+    /// The defaults configured for a package, if any.
+    pub fn project(&self, package_name: &str) -> ProjectConfig {
+        self.projects.get(package_name).cloned().unwrap_or_default()
+    }
+}
+
+pub fn config_file_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return Some(PathBuf::from(path));
+    }
+
+    let config_home = env::var_os(XDG_CONFIG_HOME_ENV)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(DEFAULT_CONFIG_HOME)))?;
+
+    Some(config_home.join(env!("APP_NAME")).join(CONFIG_FILE))
+}
+
+pub fn search_roots(config: &Config) -> Result<Vec<PathBuf>> {
+    let home = home_dir().ok_or(RustrError::HomeDirNotFound)?;
+    Ok(resolve_search_roots(
+        env::var_os(SEARCH_PATH_ENV),
+        &config.search_paths,
+        &home,
+    ))
+}
+
+pub fn project_search(config: &Config) -> Result<ProjectSearch> {
+    Ok(ProjectSearch::new(
+        search_roots(config)?,
+        config.search_depth.unwrap_or(DEFAULT_SEARCH_DEPTH),
+    )
+    .with_index_file(index_file_path())
+    .with_prefix_match(config.prefix_match))
+}
+
+/// Builds the ordered list of project search roots.
+///
+/// Entries from the environment come first, followed by the config file.
+/// When neither provides a root, the canonical `~/dev/Rust` location is used.
+pub fn resolve_search_roots(
+    env_paths: Option<OsString>,
+    config_paths: &[String],
+    home: &Path,
+) -> Vec<PathBuf> {
+    let mut roots = Vec::<PathBuf>::new();
+
+    let env_roots = env_paths
+        .as_deref()
+        .map(|paths| env::split_paths(paths).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let config_roots = config_paths.iter().map(PathBuf::from);
+
+    for root in env_roots.into_iter().chain(config_roots) {
+        if root.as_os_str().is_empty() {
+            continue;
+        }
+        let root = expand_tilde(&root, home);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    if roots.is_empty() {
+        roots.push(home.join(RUST_PROJECTS_DIR));
+    }
+
+    roots
+}
+
+fn expand_tilde(path: &Path, home: &Path) -> PathBuf {
+    match path.strip_prefix("~") {
+        Ok(rest) => home.join(rest),
+        Err(_) => path.to_path_buf(),
+    }
+}
@@ -5,7 +5,61 @@ pub const RUST_PROJECTS_DIR: &str = "dev/Rust";
 pub const BIN_DIR: &str = "bin";
 pub const TARGET_DIR: &str = "target";
 pub const RELEASE_DIR: &str = "release";
-pub const NAME_KEY: &str = "name =";
+pub const DEBUG_DIR: &str = "debug";
+
+// Cargo profiles and target directory configuration
+pub const DEV_PROFILE: &str = "dev";
+pub const RELEASE_PROFILE: &str = "release";
+pub const TEST_PROFILE: &str = "test";
+pub const BENCH_PROFILE: &str = "bench";
+pub const TARGET_DIR_ENV: &str = "CARGO_TARGET_DIR";
+pub const BUILD_TARGET_DIR_ENV: &str = "CARGO_BUILD_TARGET_DIR";
+pub const CARGO_CONFIG_DIR: &str = ".cargo";
+pub const CARGO_CONFIG_FILES: &[&str] = &["config.toml", "config"];
+
+// Cargo JSON build messages
+pub const NO_RUN_FLAG: &str = "--no-run";
+pub const JSON_MESSAGE_FORMAT: &str = "--message-format=json-render-diagnostics";
+pub const COMPILER_ARTIFACT_REASON: &str = "compiler-artifact";
+pub const BIN_KIND: &str = "bin";
+pub const EXAMPLE_KIND: &str = "example";
+
+// Cargo target layout
+pub const SRC_DIR: &str = "src";
+pub const MAIN_SOURCE_FILE: &str = "main.rs";
+pub const BIN_SOURCE_DIR: &str = "bin";
+pub const EXAMPLES_DIR: &str = "examples";
+
+// Child process handling
+pub const DEFAULT_GRACE_PERIOD_SECS: u64 = 5;
+
+// Exit codes
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_BUILD_FAILED: i32 = 3;
+pub const EXIT_TESTS_FAILED: i32 = 4;
+pub const EXIT_COMMAND_NOT_FOUND: i32 = 127;
+pub const EXIT_SIGNAL_BASE: i32 = 128;
+
+// Toolchain selection
+pub const TOOLCHAIN_ENV: &str = "RUSTUP_TOOLCHAIN";
+pub const TOOLCHAIN_FILES: &[&str] = &["rust-toolchain", "rust-toolchain.toml"];
+
+// Project search configuration
+pub const SEARCH_PATH_ENV: &str = "RUSTR_PATH";
+pub const CONFIG_ENV: &str = "RUSTR_CONFIG";
+pub const XDG_CONFIG_HOME_ENV: &str = "XDG_CONFIG_HOME";
+pub const DEFAULT_CONFIG_HOME: &str = ".config";
+pub const CONFIG_FILE: &str = "config.toml";
+pub const DEFAULT_SEARCH_DEPTH: usize = 3;
+pub const SKIPPED_DIRS: &[&str] = &[TARGET_DIR, ".git"];
+pub const MAX_SUGGESTIONS: usize = 3;
+
+// Project index cache
+pub const XDG_CACHE_HOME_ENV: &str = "XDG_CACHE_HOME";
+pub const DEFAULT_CACHE_HOME: &str = ".cache";
+pub const INDEX_FILE: &str = "index.json";
+pub const INDEX_VERSION: u32 = 2;
 
 // OS-specific binary extension
 #[cfg(windows)]
@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::constants::*;
+use crate::helpers::{get_binary_name, read_package_name};
+use crate::index::ProjectIndex;
+use crate::manifest::Manifest;
+use crate::project::workspace_members;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectSearch {
+    pub roots: Vec<PathBuf>,
+    pub max_depth: usize,
+    pub index_file: Option<PathBuf>,
+    pub prefix_match: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoveredProject {
+    pub package_name: Option<String>,
+    pub binary_name: Option<String>,
+    pub dir: PathBuf,
+    pub root: PathBuf,
+    pub manifest_modified: Option<SystemTime>,
+    /// When `src` and `src/bin` last changed, since adding or removing a
+    /// binary there can change `binary_name`.
+    pub sources_modified: [Option<SystemTime>; 2],
+}
+
+impl DiscoveredProject {
+    fn new(dir: &Path, root: &Path) -> Self {
+        Self {
+            package_name: read_package_name(dir).ok().flatten(),
+            binary_name: get_binary_name(dir).ok(),
+            dir: dir.to_path_buf(),
+            root: root.to_path_buf(),
+            manifest_modified: manifest_modified(dir),
+            sources_modified: sources_modified(dir),
+        }
+    }
+
+    pub fn dir_name(&self) -> Option<&str> {
+        self.dir.file_name().and_then(|name| name.to_str())
+    }
+
+    /// The name this project is looked up by: its package name, or its
+    /// directory name when the manifest has no package.
+    pub fn display_name(&self) -> Option<&str> {
+        self.package_name.as_deref().or_else(|| self.dir_name())
+    }
+
+    /// Whether the manifest and the autodiscovered binaries are unchanged
+    /// since this project was discovered.
+    pub fn is_fresh(&self) -> bool {
+        self.manifest_modified.is_some()
+            && manifest_modified(&self.dir) == self.manifest_modified
+            && sources_modified(&self.dir) == self.sources_modified
+    }
+}
+
+impl ProjectSearch {
+    pub fn new(roots: Vec<PathBuf>, max_depth: usize) -> Self {
+        Self {
+            roots,
+            max_depth,
+            index_file: None,
+            prefix_match: false,
+        }
+    }
+
+    pub fn with_index_file(mut self, index_file: Option<PathBuf>) -> Self {
+        self.index_file = index_file;
+        self
+    }
+
+    pub fn with_prefix_match(mut self, prefix_match: bool) -> Self {
+        self.prefix_match = prefix_match;
+        self
+    }
+
+    /// Walks every root and returns the projects found, in root order.
+    pub fn discover(&self) -> Vec<DiscoveredProject> {
+        self.roots
+            .iter()
+            .flat_map(|root| discover_in_root(root, self.max_depth))
+            .collect()
+    }
+
+    /// Finds the projects matching `project_name`, consulting the index first.
+    ///
+    /// The index is rebuilt when the lookup misses or when any matching
+    /// manifest changed since it was indexed.
+    pub fn find(&self, project_name: &str) -> Vec<DiscoveredProject> {
+        if let Some(index) = ProjectIndex::load_for(self) {
+            let matches = find_matches(&self.roots, &index.projects, project_name);
+            if !matches.is_empty() && matches.iter().all(DiscoveredProject::is_fresh) {
+                return matches;
+            }
+        }
+
+        let index = ProjectIndex::rebuild(self);
+        find_matches(&self.roots, &index.projects, project_name)
+    }
+
+    /// Finds the projects whose package name starts with `prefix`, in the
+    /// first root that has any.
+    pub fn find_by_prefix(&self, prefix: &str) -> Vec<DiscoveredProject> {
+        let projects = self.known_projects();
+        for root in &self.roots {
+            let matches = projects
+                .iter()
+                .filter(|project| &project.root == root)
+                .filter(|project| {
+                    project
+                        .package_name
+                        .as_deref()
+                        .is_some_and(|name| name.starts_with(prefix))
+                })
+                .cloned()
+                .collect::<Vec<_>>();
+            if !matches.is_empty() {
+                return matches;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Every project reachable from the search roots, from the index when it's
+    /// usable and from a fresh walk otherwise.
+    pub fn known_projects(&self) -> Vec<DiscoveredProject> {
+        match ProjectIndex::load_for(self) {
+            Some(index) => index.projects,
+            None => ProjectIndex::rebuild(self).projects,
+        }
+    }
+}
+
+/// Picks the projects matching `project_name` in the first root that has any.
+///
+/// Package names from `Cargo.toml` take priority over directory names, so a
+/// directory that merely shares the name doesn't shadow the real package.
+pub fn find_matches(
+    roots: &[PathBuf],
+    projects: &[DiscoveredProject],
+    project_name: &str,
+) -> Vec<DiscoveredProject> {
+    for root in roots {
+        let in_root = projects
+            .iter()
+            .filter(|project| &project.root == root)
+            .collect::<Vec<_>>();
+
+        let by_package = in_root
+            .iter()
+            .filter(|project| project.package_name.as_deref() == Some(project_name))
+            .map(|project| (*project).clone())
+            .collect::<Vec<_>>();
+        if !by_package.is_empty() {
+            return by_package;
+        }
+
+        let by_dir = in_root
+            .iter()
+            .filter(|project| project.dir_name() == Some(project_name))
+            .map(|project| (*project).clone())
+            .collect::<Vec<_>>();
+        if !by_dir.is_empty() {
+            return by_dir;
+        }
+    }
+
+    Vec::new()
+}
+
+fn discover_in_root(root: &Path, max_depth: usize) -> Vec<DiscoveredProject> {
+    let mut projects = Vec::new();
+    walk_dir(root, root, 1, max_depth, &mut projects);
+    projects
+}
+
+fn walk_dir(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    projects: &mut Vec<DiscoveredProject>,
+) {
+    if depth > max_depth {
+        return;
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    let mut subdirs = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && !is_skipped_dir(path))
+        .collect::<Vec<_>>();
+    subdirs.sort();
+
+    for subdir in subdirs {
+        if subdir.join(CARGO_TOML).is_file() {
+            push_project(projects, &subdir, root);
+
+            // Workspace members may live deeper than the search depth
+            if let Ok(Manifest {
+                workspace: Some(workspace),
+                ..
+            }) = Manifest::load(&subdir)
+            {
+                for member in workspace_members(&subdir, &workspace) {
+                    push_project(projects, &member, root);
+                }
+            }
+        }
+        walk_dir(root, &subdir, depth + 1, max_depth, projects);
+    }
+}
+
+fn push_project(projects: &mut Vec<DiscoveredProject>, dir: &Path, root: &Path) {
+    if !projects.iter().any(|project| project.dir == dir) {
+        projects.push(DiscoveredProject::new(dir, root));
+    }
+}
+
+fn is_skipped_dir(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+}
+
+fn manifest_modified(dir: &Path) -> Option<SystemTime> {
+    modified(&dir.join(CARGO_TOML))
+}
+
+fn sources_modified(dir: &Path) -> [Option<SystemTime>; 2] {
+    let src = dir.join(SRC_DIR);
+    [modified(&src), modified(&src.join(BIN_DIR))]
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
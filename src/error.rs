@@ -0,0 +1,205 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::constants::*;
+use crate::process::CargoError;
+use crate::suggest::did_you_mean;
+use crate::targets::TargetKind;
+
+pub type Result<T, E = RustrError> = std::result::Result<T, E>;
+
+/// Everything that can go wrong while resolving and building a project.
+#[derive(Debug)]
+pub enum RustrError {
+    /// The command line couldn't be parsed.
+    Usage(String),
+    /// No project with this name was found under the search roots.
+    ProjectNotFound {
+        name: String,
+        roots: Vec<PathBuf>,
+        max_depth: usize,
+        suggestions: Vec<String>,
+    },
+    /// Several projects match the name.
+    AmbiguousProject { name: String, dirs: Vec<PathBuf> },
+    /// A workspace with several packages where none could be picked.
+    AmbiguousPackage {
+        workspace: PathBuf,
+        packages: Vec<String>,
+    },
+    /// Several binary targets and nothing says which one to run.
+    AmbiguousBinary { names: Vec<String> },
+    /// The package has no target of this kind with this name.
+    TargetNotFound {
+        kind: TargetKind,
+        name: String,
+        dir: PathBuf,
+        available: Vec<String>,
+        suggestions: Vec<String>,
+    },
+    /// The config file isn't valid TOML or has unknown keys.
+    Config { path: PathBuf, message: String },
+    /// The project index cache couldn't be read back or written.
+    Index { path: PathBuf, message: String },
+    /// A manifest isn't valid TOML or doesn't have the expected shape.
+    ManifestParse { path: PathBuf, message: String },
+    /// A manifest without a `[package].name`.
+    MissingPackageName { path: PathBuf },
+    /// A cargo command failed.
+    CargoFailed(CargoError),
+    /// The build finished but the binary isn't where it should be.
+    BinaryMissing { path: PathBuf },
+    /// rustr was asked to run itself.
+    SelfRun { name: String },
+    /// The home directory is needed (for the default search root or
+    /// `--release-bin` destination) but can't be found.
+    HomeDirNotFound,
+    /// An I/O operation failed.
+    Io { context: String, source: io::Error },
+}
+
+impl RustrError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Self::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// The exit code rustr reports this error with.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => EXIT_USAGE,
+            Self::CargoFailed(error) => error.exit_code(),
+            _ => EXIT_FAILURE,
+        }
+    }
+}
+
+impl fmt::Display for RustrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Usage(message) => write!(f, "{}", message),
+            Self::ProjectNotFound {
+                name,
+                roots,
+                max_depth,
+                suggestions,
+            } => {
+                write!(
+                    f,
+                    "Project directory not found: {}\nSearched (depth {}):\n{}",
+                    name,
+                    max_depth,
+                    indented(roots.iter().map(|root| root.display()))
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            Self::AmbiguousProject { name, dirs } => write!(
+                f,
+                "Project name '{}' is ambiguous. Matching projects:\n{}",
+                name,
+                indented(dirs.iter().map(|dir| dir.display()))
+            ),
+            Self::AmbiguousPackage {
+                workspace,
+                packages,
+            } => write!(
+                f,
+                "{} is a workspace with several packages. Choose a member package:\n{}",
+                workspace.display(),
+                indented(packages)
+            ),
+            Self::AmbiguousBinary { names } => write!(
+                f,
+                "Multiple binary targets found. Set [package].default-run in {} or choose one with --bin:\n{}",
+                CARGO_TOML,
+                indented(names)
+            ),
+            Self::TargetNotFound {
+                kind,
+                name,
+                dir,
+                available,
+                suggestions,
+            } => {
+                let (singular, plural) = kind.describe();
+                let available = if available.is_empty() {
+                    "(none)".to_owned()
+                } else {
+                    available.join(", ")
+                };
+                write!(
+                    f,
+                    "No {} target named '{}' in {}\nAvailable {}: {}",
+                    singular,
+                    name,
+                    dir.display(),
+                    plural,
+                    available
+                )?;
+                write_suggestions(f, suggestions)
+            }
+            Self::Config { path, message } => {
+                write!(f, "Invalid config file {}: {}", path.display(), message)
+            }
+            Self::Index { path, message } => {
+                write!(f, "Invalid project index {}: {}", path.display(), message)
+            }
+            Self::ManifestParse { path, message } => {
+                write!(f, "Invalid {}: {}", path.display(), message)
+            }
+            Self::MissingPackageName { path } => {
+                write!(f, "Could not find project name in {}", path.display())
+            }
+            Self::CargoFailed(error) => write!(f, "{}", error),
+            Self::BinaryMissing { path } => write!(
+                f,
+                "Binary not found: {}. Make sure the build completed successfully.",
+                path.display()
+            ),
+            Self::SelfRun { name } => write!(
+                f,
+                "Running {} on itself is not supported.\n\
+                Use `cargo build`, `cargo test`, or similar directly inside the repository.",
+                name
+            ),
+            Self::HomeDirNotFound => write!(f, "Could not find home directory"),
+            Self::Io { context, .. } => write!(f, "{}", context),
+        }
+    }
+}
+
+impl Error for RustrError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<CargoError> for RustrError {
+    fn from(error: CargoError) -> Self {
+        Self::CargoFailed(error)
+    }
+}
+
+fn indented<I, T>(lines: I) -> String
+where
+    I: IntoIterator<Item = T>,
+    T: fmt::Display,
+{
+    lines
+        .into_iter()
+        .map(|line| format!("  {}", line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn write_suggestions(f: &mut fmt::Formatter<'_>, suggestions: &[String]) -> fmt::Result {
+    let suggestions = suggestions.iter().map(String::as_str).collect::<Vec<_>>();
+    write!(f, "{}", did_you_mean(&suggestions))
+}
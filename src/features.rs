@@ -0,0 +1,44 @@
+/// The cargo feature flags to build with.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FeatureArgs {
+    pub features: Vec<String>,
+    pub all_features: bool,
+    pub no_default_features: bool,
+}
+
+impl FeatureArgs {
+    /// Adds feature names from a `--features` value, which may hold several
+    /// names separated by commas or spaces.
+    pub fn add_features(&mut self, value: &str) {
+        for feature in value.split([',', ' ']).filter(|name| !name.is_empty()) {
+            if !self.features.iter().any(|existing| existing == feature) {
+                self.features.push(feature.to_owned());
+            }
+        }
+    }
+
+    /// Combines these flags with `defaults`, keeping the features of both.
+    pub fn merged_with(&self, defaults: &FeatureArgs) -> FeatureArgs {
+        let mut merged = defaults.clone();
+        for feature in &self.features {
+            merged.add_features(feature);
+        }
+        merged.all_features |= self.all_features;
+        merged.no_default_features |= self.no_default_features;
+        merged
+    }
+
+    pub fn cargo_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if !self.features.is_empty() {
+            args.extend(["--features".into(), self.features.join(",")]);
+        }
+        if self.all_features {
+            args.push("--all-features".into());
+        }
+        if self.no_default_features {
+            args.push("--no-default-features".into());
+        }
+        args
+    }
+}
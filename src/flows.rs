@@ -0,0 +1,321 @@
+use home::home_dir;
+use serde::Serialize;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::artifacts::profile_command;
+use crate::cli::{Action, CliArgs};
+use crate::config::ProjectConfig;
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+use crate::helpers::{
+    banner, get_target, locate_binary, run_cargo_build, run_cargo_command, run_cargo_tests,
+};
+use crate::project::Project;
+use crate::runner::{CommandRunner, RecordedCommand, RecordingRunner};
+use crate::targets::Target;
+use crate::toolchain::Toolchain;
+
+/// Everything a flow needs to know about the project it works on.
+#[derive(Clone, Copy, Debug)]
+pub struct FlowContext<'a> {
+    pub args: &'a CliArgs,
+    pub project: &'a Project,
+    pub project_config: &'a ProjectConfig,
+    pub toolchain: &'a Toolchain,
+}
+
+/// Runs the actions the arguments ask for in pipeline order (test, build,
+/// release, release-bin), stopping at the first failure, or builds and runs
+/// the target when there are none. Returns the exit code to end with.
+/// Progress messages go to `report`.
+pub fn run_flow(
+    context: FlowContext,
+    runner: &mut dyn CommandRunner,
+    report: &mut dyn FnMut(String),
+) -> Result<i32> {
+    let flow = Flow::new(context);
+    let actions = context.args.actions();
+    if actions.is_empty() {
+        return flow.run(runner);
+    }
+
+    flow.report_banner(report);
+    if actions.len() > 1 {
+        let names = actions
+            .iter()
+            .map(Action::to_string)
+            .collect::<Vec<_>>()
+            .join(", ");
+        report(format!("Running {}", names));
+    }
+
+    for action in &actions {
+        match action {
+            Action::Test => flow.test(runner, report)?,
+            Action::Build => flow.build(runner, report)?,
+            Action::Release => flow.release(runner, report)?,
+            Action::ReleaseBin(dest) => flow.release_bin(dest.as_deref(), runner, report)?,
+        }
+    }
+    Ok(0)
+}
+
+/// What a flow would do, worked out without building, running or copying
+/// anything.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Plan {
+    pub package: String,
+    pub project_dir: PathBuf,
+    pub cargo_dir: PathBuf,
+    /// The binary or example the flow works on; `None` when testing the
+    /// whole package.
+    pub target: Option<String>,
+    pub toolchain: String,
+    pub steps: Vec<RecordedCommand>,
+}
+
+/// Plans the flow the arguments ask for by running it against a
+/// [`RecordingRunner`]. Paths to built binaries are where cargo would put them.
+pub fn plan_flow(context: FlowContext) -> Result<Plan> {
+    let mut runner = RecordingRunner::new();
+    run_flow(context, &mut runner, &mut |_| {})?;
+
+    let target = Flow::new(context).selected_target()?;
+    Ok(Plan {
+        package: context.project.package_name.clone(),
+        project_dir: context.project.dir.clone(),
+        cargo_dir: context.project.cargo_dir().to_path_buf(),
+        target: target.map(|target| target.to_string()),
+        toolchain: context.toolchain.to_string(),
+        steps: runner.commands,
+    })
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Project: {} ({})",
+            self.package,
+            self.project_dir.display()
+        )?;
+        writeln!(f, "Cargo runs in: {}", self.cargo_dir.display())?;
+        if let Some(target) = &self.target {
+            writeln!(f, "Target: {}", target)?;
+        }
+        writeln!(f, "Toolchain: {}", self.toolchain)?;
+        writeln!(f, "Steps:")?;
+        for (number, step) in self.steps.iter().enumerate() {
+            writeln!(f, "  {}. {}", number + 1, step.command_line())?;
+        }
+        write!(f, "Dry run: nothing was built, run or copied")
+    }
+}
+
+struct Flow<'a> {
+    context: FlowContext<'a>,
+    cargo_flags: Vec<String>,
+}
+
+impl<'a> Flow<'a> {
+    fn new(context: FlowContext<'a>) -> Self {
+        let mut cargo_flags = context.args.cargo_flags();
+        cargo_flags.extend(
+            context
+                .args
+                .features
+                .merged_with(&context.project_config.feature_args())
+                .cargo_args(),
+        );
+        Self {
+            context,
+            cargo_flags,
+        }
+    }
+
+    fn report_banner(&self, report: &mut dyn FnMut(String)) {
+        report(banner());
+        report(format!("Toolchain: {}", self.context.toolchain));
+    }
+
+    fn cargo_args(&self, command: &[&str], target: Option<&Target>) -> Vec<OsString> {
+        let mut cargo_args = self.context.project.cargo_args(command);
+        if let Some(toolchain) = self.context.toolchain.cargo_arg() {
+            cargo_args.insert(0, toolchain.into());
+        }
+        cargo_args.extend(self.cargo_flags.iter().map(OsString::from));
+        // Enable whatever the selected target needs to be built at all
+        if let Some(target) = target {
+            cargo_args.extend(
+                target
+                    .required_feature_args()
+                    .into_iter()
+                    .map(OsString::from),
+            );
+        }
+        cargo_args
+    }
+
+    /// The target the flow works on. Tests on their own cover the whole
+    /// package unless a binary or example was picked.
+    fn selected_target(&self) -> Result<Option<Target>> {
+        if self.context.args.actions() == [Action::Test] {
+            return self.test_target();
+        }
+        self.target().map(Some)
+    }
+
+    fn test_target(&self) -> Result<Option<Target>> {
+        let args = self.context.args;
+        if args.bin.is_none() && args.example.is_none() {
+            return Ok(None);
+        }
+        self.target().map(Some)
+    }
+
+    fn target(&self) -> Result<Target> {
+        let args = self.context.args;
+        get_target(
+            &self.context.project.dir,
+            args.bin.as_deref(),
+            args.example.as_deref(),
+        )
+    }
+
+    // --profile replaces the dev or release profile each mode builds with
+    fn build_profile(&self) -> &'a str {
+        self.context.args.profile.as_deref().unwrap_or(DEV_PROFILE)
+    }
+
+    fn release_profile(&self) -> &'a str {
+        self.context
+            .args
+            .profile
+            .as_deref()
+            .unwrap_or(RELEASE_PROFILE)
+    }
+
+    fn run_profile(&self) -> &'a str {
+        let args = self.context.args;
+        match args.profile.as_deref() {
+            Some(profile) => profile,
+            None if args.debug_run || self.context.project_config.debug_run => DEV_PROFILE,
+            None => RELEASE_PROFILE,
+        }
+    }
+
+    fn test(&self, runner: &mut dyn CommandRunner, report: &mut dyn FnMut(String)) -> Result<()> {
+        let args = self.context.args;
+        let target = self.test_target()?;
+        run_cargo_tests(
+            runner,
+            self.context.project.cargo_dir(),
+            self.cargo_args(
+                &profile_command("test", args.profile.as_deref()),
+                target.as_ref(),
+            ),
+        )?;
+        report("Test complete".into());
+        Ok(())
+    }
+
+    fn build(&self, runner: &mut dyn CommandRunner, report: &mut dyn FnMut(String)) -> Result<()> {
+        let target = self.target()?;
+        report(format!("Building project: {}", target));
+        run_cargo_command(
+            runner,
+            self.context.project.cargo_dir(),
+            self.cargo_args(
+                &profile_command("build", Some(self.build_profile())),
+                Some(&target),
+            ),
+        )?;
+        report("Build complete".into());
+        Ok(())
+    }
+
+    fn release(
+        &self,
+        runner: &mut dyn CommandRunner,
+        report: &mut dyn FnMut(String),
+    ) -> Result<()> {
+        let target = self.target()?;
+        let profile = self.release_profile();
+        report(format!(
+            "Building {} version of project: {}",
+            profile, target
+        ));
+        run_cargo_command(
+            runner,
+            self.context.project.cargo_dir(),
+            self.cargo_args(&profile_command("build", Some(profile)), Some(&target)),
+        )?;
+        report("Release build complete".into());
+        Ok(())
+    }
+
+    fn release_bin(
+        &self,
+        dest: Option<&str>,
+        runner: &mut dyn CommandRunner,
+        report: &mut dyn FnMut(String),
+    ) -> Result<()> {
+        let target = self.target()?;
+        let profile = self.release_profile();
+        report(format!(
+            "Building {} version of project: {}",
+            profile, target
+        ));
+        let executables = run_cargo_build(
+            runner,
+            self.context.project.cargo_dir(),
+            self.cargo_args(&profile_command("build", Some(profile)), Some(&target)),
+        )?;
+        let source = locate_binary(
+            &executables,
+            self.context.project,
+            self.context.args.target_triple.as_deref(),
+            profile,
+            &target,
+        );
+
+        report(format!(
+            "Copying {} to {}",
+            target.name,
+            dest.unwrap_or(BIN_DIR)
+        ));
+        let dest_dir = match dest {
+            Some(dir) => PathBuf::from(dir),
+            None => home_dir().ok_or(RustrError::HomeDirNotFound)?.join(BIN_DIR),
+        };
+        runner.copy(&source, &dest_dir)?;
+        report(format!("Copied {} to {}", target.name, dest_dir.display()));
+        report("Done".into());
+        Ok(())
+    }
+
+    /// Builds the target and runs it with the remaining arguments, passing
+    /// its exit code through.
+    fn run(&self, runner: &mut dyn CommandRunner) -> Result<i32> {
+        let target = self.target()?;
+
+        // Build in release mode, or dev mode for --debug-run, unless a profile was given
+        let profile = self.run_profile();
+        let executables = run_cargo_build(
+            runner,
+            self.context.project.cargo_dir(),
+            self.cargo_args(&profile_command("build", Some(profile)), Some(&target)),
+        )?;
+
+        let binary_path = locate_binary(
+            &executables,
+            self.context.project,
+            self.context.args.target_triple.as_deref(),
+            profile,
+            &target,
+        );
+        runner.binary(&binary_path, &self.context.args.project_args)
+    }
+}
@@ -1,242 +1,218 @@
-use anyhow::{Context, Result};
 use colored::*;
-use home::home_dir;
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
 
+use crate::artifacts::{Executable, binary_path, example_path, find_executable};
 use crate::constants::*;
-
-#[derive(Default)]
-struct BinTarget {
-    name: Option<String>,
-    path: Option<String>,
-}
-
-pub fn print_banner() {
+use crate::discovery::{DiscoveredProject, ProjectSearch};
+use crate::error::{Result, RustrError};
+use crate::manifest::{BinTarget, Manifest};
+use crate::process::{CargoError, CargoFailure, CommandStatus};
+use crate::project::Project;
+use crate::runner::CommandRunner;
+use crate::suggest::closest_matches;
+use crate::targets::{Target, TargetKind, discover_targets, find_target, select_target};
+
+pub fn banner() -> String {
     let app_name = env!("APP_NAME");
     let app_version = env!("APP_VERSION");
     let app_build = env!("APP_BUILD");
 
-    println!("{} v{} (build {})", app_name, app_version, app_build);
-}
-
-pub fn print_help() {
-    print_banner();
-
-    println!("\nRust/Cargo Task Runner\n");
-
-    println!(
-        "{} {} [OPTIONS] [PROJECT_NAME] [ARGS...]\n",
-        "Usage:".bold().underline(),
-        "rustr".bold()
-    );
-
-    println!("{}", "Arguments:".bold().underline());
-    println!("  [PROJECT_NAME]");
-    println!("          Project name");
-    println!("  [ARGS]...");
-    println!("          Arguments to pass to the target project\n");
-
-    println!("{}", "Options:".bold().underline());
-    println!("      {}", "--test".bold());
-    println!("          Run tests for the project");
-    println!("      {}", "--build".bold());
-    println!("          Build the project");
-    println!("      {}", "--release".bold());
-    println!("          Build in release mode");
-    println!("      {} [<DESTINATION>]", "--release-bin".bold());
-    println!("          Build in release mode and copy to ~/bin (or specified path)");
-    println!("      {} <PROJECT>", "--project".bold());
-    println!("          Explicitly specify the target project");
-    println!("      --");
-    println!("          Stop option parsing and pass remaining arguments to the target project");
-    println!("  -h, --help");
-    println!("          Print help");
-    println!("  -V, --version");
-    println!("          Print version");
+    format!("{} v{} (build {})", app_name, app_version, app_build)
 }
 
-fn bail(message: &str) -> Result<()> {
-    print_banner();
-    anyhow::bail!("{}", message);
+/// The `--help` text, starting with the banner.
+pub fn help() -> String {
+    [
+        banner(),
+        "\nRust/Cargo Task Runner\n".to_owned(),
+        format!(
+            "{} {} [+TOOLCHAIN] [OPTIONS] [PROJECT_NAME] [ARGS...]\n",
+            "Usage:".bold().underline(),
+            "rustr".bold()
+        ),
+        "Arguments:".bold().underline().to_string(),
+        "  [+TOOLCHAIN]".to_owned(),
+        "          Rustup toolchain to build with, e.g. +nightly".to_owned(),
+        "  [PROJECT_NAME]".to_owned(),
+        "          Project name".to_owned(),
+        "  [ARGS]...".to_owned(),
+        "          Arguments to pass to the target project\n".to_owned(),
+        "Options:".bold().underline().to_string(),
+        format!("      {}", "--test".bold()),
+        "          Run tests for the project".to_owned(),
+        format!("      {}", "--build".bold()),
+        "          Build the project".to_owned(),
+        format!("      {}", "--release".bold()),
+        "          Build in release mode".to_owned(),
+        format!("      {} [<DESTINATION>]", "--release-bin".bold()),
+        "          Build in release mode and copy to ~/bin (or specified path)".to_owned(),
+        format!("      {}", "--debug-run".bold()),
+        "          Build in dev mode and run the debug binary".to_owned(),
+        format!("  {}, {}", "-n".bold(), "--dry-run".bold()),
+        "          Print what would be built, run or copied without doing it".to_owned(),
+        format!("      {} <PROJECT>", "--project".bold()),
+        "          Explicitly specify the target project".to_owned(),
+        format!("      {} <NAME>", "--bin".bold()),
+        "          Build, run or copy the named binary target".to_owned(),
+        format!("      {} <NAME>", "--example".bold()),
+        "          Build, run or copy the named example".to_owned(),
+        format!("      {} <TRIPLE>", "--target".bold()),
+        "          Build for the given target triple".to_owned(),
+        format!("      {} <ARG>", "--cargo-arg".bold()),
+        "          Pass an argument to every cargo command (repeatable)".to_owned(),
+        format!("      {} <NAME>", "--profile".bold()),
+        "          Build with the named cargo profile instead of dev or release".to_owned(),
+        format!("  {}, {} <FEATURES>", "-F".bold(), "--features".bold()),
+        "          Space or comma separated list of features to activate".to_owned(),
+        format!("      {}", "--all-features".bold()),
+        "          Activate all available features".to_owned(),
+        format!("      {}", "--no-default-features".bold()),
+        "          Do not activate the `default` feature".to_owned(),
+        format!("      {}", "--list".bold()),
+        "          List the projects found under the search roots".to_owned(),
+        format!("      {}", "--json".bold()),
+        "          Print the --list or --dry-run output as JSON".to_owned(),
+        format!("      {}", "--reindex".bold()),
+        "          Rebuild the project index before resolving the project".to_owned(),
+        "      --".to_owned(),
+        "          Stop option parsing and pass remaining arguments to the target project"
+            .to_owned(),
+        "  -h, --help".to_owned(),
+        "          Print help".to_owned(),
+        "  -V, --version".to_owned(),
+        "          Print version".to_owned(),
+    ]
+    .join("\n")
 }
 
-fn is_self_project() -> Result<bool> {
-    let app_name = env!("APP_NAME");
-    if Path::new(CARGO_TOML).exists() {
-        let contents = fs::read_to_string(CARGO_TOML)?;
-        for line in contents.lines() {
-            if line.trim().starts_with(NAME_KEY) {
-                let name = line
-                    .split('=')
-                    .nth(1)
-                    .context(format!("Invalid {} format", CARGO_TOML))?
-                    .trim()
-                    .trim_matches(|c| c == '"' || c == ' ');
-                return Ok(name == app_name);
-            }
-        }
+pub fn is_self_project(project_dir: &Path) -> Result<bool> {
+    if !project_dir.join(CARGO_TOML).exists() {
+        return Ok(false);
     }
-    Ok(false)
+    let manifest = Manifest::load(project_dir)?;
+    Ok(manifest.package_name() == Some(env!("APP_NAME")))
 }
 
 pub fn check_self_run(explicit: Option<&str>, positional: Option<&str>) -> Result<()> {
     let app_name = env!("APP_NAME");
     if explicit == Some(app_name)
         || positional == Some(app_name)
-        || (explicit.is_none() && positional.is_none() && is_self_project()?)
+        || (explicit.is_none() && positional.is_none() && is_self_project(Path::new("."))?)
     {
-        bail(&format!(
-            "Running {} on itself is not supported.\n\
-            Use `cargo build`, `cargo test`, or similar directly inside the repository.",
-            app_name
-        ))?;
+        return Err(RustrError::SelfRun {
+            name: app_name.to_owned(),
+        });
     }
     Ok(())
 }
 
-pub fn find_project_dir(project_name: &str) -> Result<PathBuf> {
-    // Check if we're in a project directory
-    if Path::new(CARGO_TOML).exists() {
-        // Try to read its package name - if that fails, fall back to home search
-        if let Ok(current_name) = get_package_name(&PathBuf::from(".")) {
-            if current_name == project_name {
-                return Ok(PathBuf::from("."));
-            }
-        }
+pub fn find_project_dir(project_name: &str, search: &ProjectSearch) -> Result<PathBuf> {
+    // Check if we're in a project directory; if its package name can't be read,
+    // fall back to the search roots
+    if Path::new(CARGO_TOML).exists()
+        && let Ok(Some(current_name)) = read_package_name(Path::new("."))
+        && current_name == project_name
+    {
+        return Ok(PathBuf::from("."));
+    }
+
+    let mut matches = search.find(project_name);
+
+    // Fall back to an unambiguous package name prefix when enabled
+    if matches.is_empty() && search.prefix_match {
+        matches = search.find_by_prefix(project_name);
     }
 
-    // Use the canonical project location
-    let home = home_dir().context("Could not find home directory")?;
-    let project_path = home.join(RUST_PROJECTS_DIR).join(project_name);
+    if matches.len() > 1 {
+        return Err(RustrError::AmbiguousProject {
+            name: project_name.to_owned(),
+            dirs: matches.into_iter().map(|project| project.dir).collect(),
+        });
+    }
 
-    if !project_path.exists() {
-        bail(&format!(
-            "Project directory not found: {}",
-            project_path.display()
-        ))?;
+    if let Some(project) = matches.pop() {
+        return Ok(project.dir);
     }
 
-    Ok(project_path)
+    let known_projects = search.known_projects();
+    let suggestions = closest_matches(
+        project_name,
+        known_projects
+            .iter()
+            .filter_map(DiscoveredProject::display_name),
+    );
+    Err(RustrError::ProjectNotFound {
+        name: project_name.to_owned(),
+        roots: search.roots.clone(),
+        max_depth: search.max_depth,
+        suggestions: suggestions.into_iter().map(str::to_owned).collect(),
+    })
+}
+
+/// Finds the project directory for `project_name` and resolves the package to
+/// build from it, selecting the matching member of a workspace.
+pub fn locate_project(project_name: &str, search: &ProjectSearch) -> Result<Project> {
+    let project_dir = find_project_dir(project_name, search)?;
+    Project::resolve(&project_dir, Some(project_name))
 }
 
 pub fn get_package_name(project_dir: &Path) -> Result<String> {
-    let cargo_toml = project_dir.join(CARGO_TOML);
-    let contents = fs::read_to_string(cargo_toml)?;
-    let mut in_package_section = false;
-
-    for line in contents.lines() {
-        let trimmed = line.trim();
-        if trimmed == "[package]" {
-            in_package_section = true;
-            continue;
-        }
-        if trimmed.starts_with('[') {
-            in_package_section = false;
-            continue;
-        }
-
-        if in_package_section {
-            if let Some(name) = parse_toml_string_value(trimmed, "name") {
-                return Ok(name);
-            }
-        }
-    }
+    read_package_name(project_dir)?.ok_or_else(|| RustrError::MissingPackageName {
+        path: project_dir.join(CARGO_TOML),
+    })
+}
 
-    bail(&format!("Could not find project name in {}", CARGO_TOML))?;
-    unreachable!()
+/// Reads `[package].name`, returning `None` when the manifest has no package
+/// section (e.g. a virtual workspace).
+pub fn read_package_name(project_dir: &Path) -> Result<Option<String>> {
+    let manifest = Manifest::load(project_dir)?;
+    Ok(manifest.package_name().map(str::to_owned))
 }
 
-pub fn get_binary_name(project_dir: &Path) -> Result<String> {
-    let package_name = get_package_name(project_dir)?;
-    let cargo_toml = project_dir.join(CARGO_TOML);
-    let contents = fs::read_to_string(cargo_toml)?;
-
-    let mut in_package_section = false;
-    let mut in_bin_section = false;
-    let mut default_run = None;
-    let mut autobins_enabled = true;
-    let mut explicit_bins: Vec<BinTarget> = Vec::new();
-
-    for line in contents.lines() {
-        let trimmed = line.trim();
-        if trimmed == "[package]" {
-            in_package_section = true;
-            in_bin_section = false;
-            continue;
-        }
-        if trimmed == "[[bin]]" {
-            in_package_section = false;
-            in_bin_section = true;
-            explicit_bins.push(BinTarget::default());
-            continue;
-        }
-        if trimmed.starts_with('[') {
-            in_package_section = false;
-            in_bin_section = false;
-            continue;
-        }
-
-        if in_package_section {
-            if default_run.is_none() {
-                default_run = parse_toml_string_value(trimmed, "default-run");
-            }
-            if let Some(autobins) = parse_toml_bool_value(trimmed, "autobins") {
-                autobins_enabled = autobins;
-            }
-        }
-
-        if in_bin_section {
-            if let Some(bin) = explicit_bins.last_mut() {
-                if bin.name.is_none() {
-                    bin.name = parse_toml_string_value(trimmed, "name");
-                }
-                if bin.path.is_none() {
-                    bin.path = parse_toml_string_value(trimmed, "path");
-                }
-            }
-        }
+/// Resolves the target to build: the binary or example picked with `--bin` or
+/// `--example`, otherwise the package's main binary.
+pub fn get_target(project_dir: &Path, bin: Option<&str>, example: Option<&str>) -> Result<Target> {
+    match (bin, example) {
+        (Some(name), _) => select_target(project_dir, TargetKind::Bin, name),
+        (None, Some(name)) => select_target(project_dir, TargetKind::Example, name),
+        (None, None) => get_binary_name(project_dir)
+            .map(|name| find_target(project_dir, TargetKind::Bin, &name)),
     }
+}
 
-    if let Some(default_run) = default_run {
-        return Ok(default_run);
+/// Resolves the name of the binary `cargo run` would pick for the package.
+pub fn get_binary_name(project_dir: &Path) -> Result<String> {
+    let manifest = Manifest::load(project_dir)?;
+    let package_name = manifest
+        .package_name()
+        .ok_or_else(|| RustrError::MissingPackageName {
+            path: project_dir.join(CARGO_TOML),
+        })?
+        .to_owned();
+
+    if let Some(default_run) = manifest.default_run() {
+        return Ok(default_run.to_owned());
     }
 
-    if let Some(name) = get_explicit_main_bin_name(&explicit_bins) {
+    if let Some(name) = get_explicit_main_bin_name(&manifest.bin) {
         return Ok(name);
     }
 
-    if explicit_bins
-        .iter()
-        .filter_map(|bin| bin.name.as_deref())
-        .any(|name| name == package_name)
-    {
+    let targets = discover_targets(project_dir, &manifest, TargetKind::Bin);
+    if targets.iter().any(|target| target.name == package_name) {
         return Ok(package_name);
     }
 
-    if autobins_enabled && project_dir.join("src").join("main.rs").exists() {
-        return Ok(package_name);
-    }
-
-    let mut explicit_bin_names = explicit_bins
-        .into_iter()
-        .filter_map(|bin| bin.name)
-        .collect::<Vec<_>>();
-
-    if explicit_bin_names.len() == 1 {
-        return Ok(explicit_bin_names.pop().unwrap());
-    }
-
-    if explicit_bin_names.len() > 1 {
-        bail(&format!(
-            "Multiple binary targets found in {}. Set [package].default-run or define a binary named '{}'.",
-            CARGO_TOML, package_name
-        ))?;
+    match targets.as_slice() {
+        // Fall back to the package name if no binary target exists.
+        [] => Ok(package_name),
+        [target] => Ok(target.name.clone()),
+        _ => Err(RustrError::AmbiguousBinary {
+            names: targets.into_iter().map(|target| target.name).collect(),
+        }),
     }
-
-    // Fall back to the package name if no explicit binary target exists.
-    Ok(package_name)
 }
 
 fn get_explicit_main_bin_name(bins: &[BinTarget]) -> Option<String> {
@@ -263,91 +239,133 @@ fn is_main_source_path(path: &str) -> bool {
     normalized == "src/main.rs"
 }
 
-fn parse_toml_string_value(line: &str, key: &str) -> Option<String> {
-    let (raw_key, raw_value) = line.split_once('=')?;
-    if raw_key.trim() != key {
-        return None;
-    }
+/// Runs cargo through `runner`, turning anything but a successful exit into a
+/// [`CargoError`].
+pub fn run_cargo_command<I, S>(
+    runner: &mut dyn CommandRunner,
+    project_dir: &Path,
+    args: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args = os_args(args);
+    let status = runner.cargo(project_dir, &args)?;
+    check_cargo_status(status, project_dir, &args, build_failed)
+}
 
-    let value = raw_value.trim_start();
-    if !value.starts_with('"') {
-        return None;
-    }
+/// Runs `cargo test` in two steps, building the tests with `--no-run` first,
+/// so that a compile error is reported differently from failing tests.
+pub fn run_cargo_tests<I, S>(
+    runner: &mut dyn CommandRunner,
+    project_dir: &Path,
+    args: I,
+) -> Result<()>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let args = os_args(args);
+
+    let mut build_args = args.clone();
+    build_args.push(NO_RUN_FLAG.into());
+    let status = runner.cargo(project_dir, &build_args)?;
+    check_cargo_status(status, project_dir, &build_args, build_failed)?;
+
+    let status = runner.cargo(project_dir, &args)?;
+    check_cargo_status(status, project_dir, &args, |code| {
+        CargoFailure::TestsFailed { code }
+    })
+}
 
-    let mut parsed = String::new();
-    let mut escaped = false;
-
-    for ch in value[1..].chars() {
-        if escaped {
-            parsed.push(ch);
-            escaped = false;
-            continue;
-        }
-
-        match ch {
-            '\\' => escaped = true,
-            '"' => return Some(parsed),
-            _ => parsed.push(ch),
-        }
-    }
+/// Runs a cargo build with JSON build messages and returns the executables it
+/// reported.
+pub fn run_cargo_build<I, S>(
+    runner: &mut dyn CommandRunner,
+    project_dir: &Path,
+    args: I,
+) -> Result<Vec<Executable>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    let mut args = os_args(args);
+    args.push(JSON_MESSAGE_FORMAT.into());
+    let (status, executables) = runner.cargo_build(project_dir, &args)?;
+    check_cargo_status(status, project_dir, &args, build_failed)?;
+    Ok(executables)
+}
 
-    None
+fn os_args<I, S>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<OsStr>,
+{
+    args.into_iter()
+        .map(|arg| arg.as_ref().to_os_string())
+        .collect()
 }
 
-fn parse_toml_bool_value(line: &str, key: &str) -> Option<bool> {
-    let (raw_key, raw_value) = line.split_once('=')?;
-    if raw_key.trim() != key {
-        return None;
-    }
+fn build_failed(code: Option<i32>) -> CargoFailure {
+    CargoFailure::BuildFailed { code }
+}
 
-    match raw_value.split('#').next()?.trim() {
-        "true" => Some(true),
-        "false" => Some(false),
-        _ => None,
+fn check_cargo_status(
+    status: CommandStatus,
+    project_dir: &Path,
+    args: &[OsString],
+    failure: fn(Option<i32>) -> CargoFailure,
+) -> Result<()> {
+    match status.failure(failure) {
+        None => Ok(()),
+        Some(failure) => Err(CargoError::new(project_dir, args, failure).into()),
     }
 }
 
-pub fn run_cargo_command(project_dir: &Path, args: &[&str]) -> Result<()> {
-    let status = Command::new(CARGO_COMMAND)
-        .current_dir(project_dir)
-        .args(args)
-        .status()?;
-
-    if !status.success() {
-        bail(&format!("Command '{}' failed", CARGO_COMMAND))?;
+/// The path of the built target: the executable cargo reported when there is
+/// one, otherwise where cargo would have put it.
+pub fn locate_binary(
+    executables: &[Executable],
+    project: &Project,
+    triple: Option<&str>,
+    profile: &str,
+    target: &Target,
+) -> PathBuf {
+    if let Some(path) = find_executable(executables, target.kind.cargo_kind(), &target.name) {
+        return path.to_path_buf();
     }
 
-    Ok(())
+    let target_dir = project.target_dir();
+    match target.kind {
+        TargetKind::Bin => binary_path(&target_dir, triple, profile, &target.name),
+        TargetKind::Example => example_path(&target_dir, triple, profile, &target.name),
+    }
 }
 
-pub fn copy_bin(project_dir: &Path, binary_name: &str, dest_dir: Option<&str>) -> Result<()> {
-    let dest_path = if let Some(dir) = dest_dir {
-        PathBuf::from(dir)
-    } else {
-        home_dir()
-            .context("Could not find home directory")?
-            .join(BIN_DIR)
-    };
-
-    if !dest_path.exists() {
-        fs::create_dir_all(&dest_path)?;
+/// Copies the built binary into `dest_dir`, creating it if needed, and returns
+/// the path of the copy.
+pub fn copy_bin(source: &Path, dest_dir: &Path) -> Result<PathBuf> {
+    if !dest_dir.exists() {
+        fs::create_dir_all(dest_dir).map_err(|error| {
+            RustrError::io(format!("Could not create {}", dest_dir.display()), error)
+        })?;
     }
 
-    let source = project_dir
-        .join(TARGET_DIR)
-        .join(RELEASE_DIR)
-        .join(format!("{}{}", binary_name, BINARY_EXTENSION));
-
+    let binary_missing = || RustrError::BinaryMissing {
+        path: source.to_path_buf(),
+    };
     if !source.exists() {
-        bail(&format!(
-            "Binary not found: {}. Make sure the build completed successfully.",
-            source.display()
-        ))?;
+        return Err(binary_missing());
     }
 
-    let dest = dest_path.join(format!("{}{}", binary_name, BINARY_EXTENSION));
-    fs::copy(&source, &dest)?;
-    println!("Copied {} to {}", binary_name, dest_path.display());
+    let dest = dest_dir.join(source.file_name().ok_or_else(binary_missing)?);
+    fs::copy(source, &dest).map_err(|error| {
+        RustrError::io(
+            format!("Could not copy {} to {}", source.display(), dest.display()),
+            error,
+        )
+    })?;
 
-    Ok(())
+    Ok(dest)
 }
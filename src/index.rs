@@ -0,0 +1,88 @@
+use home::home_dir;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::*;
+use crate::discovery::{DiscoveredProject, ProjectSearch};
+use crate::error::{Result, RustrError};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectIndex {
+    pub version: u32,
+    pub roots: Vec<PathBuf>,
+    pub max_depth: usize,
+    pub projects: Vec<DiscoveredProject>,
+}
+
+impl ProjectIndex {
+    pub fn build(search: &ProjectSearch) -> Self {
+        Self {
+            version: INDEX_VERSION,
+            roots: search.roots.clone(),
+            max_depth: search.max_depth,
+            projects: search.discover(),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path).map_err(|error| {
+            RustrError::io(
+                format!("Could not read project index {}", path.display()),
+                error,
+            )
+        })?;
+        serde_json::from_str(&contents).map_err(|error| index_error(path, error))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let write_error = |error| {
+            RustrError::io(
+                format!("Could not write project index {}", path.display()),
+                error,
+            )
+        };
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(write_error)?;
+        }
+        let contents = serde_json::to_string(self).map_err(|error| index_error(path, error))?;
+        fs::write(path, contents).map_err(write_error)
+    }
+
+    /// Loads the index for `search`, ignoring one that is unreadable or was
+    /// built for different roots, depth or format version.
+    pub fn load_for(search: &ProjectSearch) -> Option<Self> {
+        let index = Self::load(search.index_file.as_deref()?).ok()?;
+        let matches_search = index.version == INDEX_VERSION
+            && index.roots == search.roots
+            && index.max_depth == search.max_depth;
+        matches_search.then_some(index)
+    }
+
+    /// Rebuilds the index and writes it back. The index is only a cache, so a
+    /// failure to persist it doesn't stop the lookup that triggered it.
+    pub fn rebuild(search: &ProjectSearch) -> Self {
+        let index = Self::build(search);
+        if let Some(path) = &search.index_file {
+            let _ = index.save(path);
+        }
+        index
+    }
+}
+
+fn index_error(path: &Path, error: serde_json::Error) -> RustrError {
+    RustrError::Index {
+        path: path.to_path_buf(),
+        message: error.to_string(),
+    }
+}
+
+pub fn index_file_path() -> Option<PathBuf> {
+    let cache_home = env::var_os(XDG_CACHE_HOME_ENV)
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(DEFAULT_CACHE_HOME)))?;
+
+    Some(cache_home.join(env!("APP_NAME")).join(INDEX_FILE))
+}
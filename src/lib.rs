@@ -1,3 +1,18 @@
+pub mod artifacts;
 pub mod cli;
+pub mod config;
 pub mod constants;
+pub mod discovery;
+pub mod error;
+pub mod features;
+pub mod flows;
 pub mod helpers;
+pub mod index;
+pub mod list;
+pub mod manifest;
+pub mod process;
+pub mod project;
+pub mod runner;
+pub mod suggest;
+pub mod targets;
+pub mod toolchain;
@@ -0,0 +1,153 @@
+use colored::*;
+use home::home_dir;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::artifacts::{binary_path, resolve_target_dir};
+use crate::constants::*;
+use crate::discovery::{DiscoveredProject, ProjectSearch};
+use crate::index::ProjectIndex;
+use crate::project::find_workspace_root;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ProjectListing {
+    pub name: String,
+    pub path: PathBuf,
+    pub binary: Option<String>,
+    pub release_binary: Option<PathBuf>,
+    /// Modification time of the release binary, in seconds since the Unix epoch.
+    pub last_build: Option<u64>,
+}
+
+impl ProjectListing {
+    /// Describes `project`, looking for its release binary in the target
+    /// directory `target_dir` resolves for its cargo directory.
+    pub fn from_project(
+        project: &DiscoveredProject,
+        target_dir: &dyn Fn(&Path) -> PathBuf,
+    ) -> Self {
+        let cargo_dir = find_workspace_root(&project.dir).unwrap_or_else(|| project.dir.clone());
+        let target_dir = target_dir(&cargo_dir);
+        let release_binary = project
+            .binary_name
+            .as_deref()
+            .map(|binary| binary_path(&target_dir, None, RELEASE_PROFILE, binary))
+            .filter(|path| path.is_file());
+        let last_build = release_binary
+            .as_deref()
+            .and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|age| age.as_secs());
+
+        Self {
+            name: project.display_name().unwrap_or_default().to_owned(),
+            path: project.dir.clone(),
+            binary: project.binary_name.clone(),
+            release_binary,
+            last_build,
+        }
+    }
+}
+
+/// Lists every project reachable from the search roots, refreshing the index
+/// first if any manifest changed since it was built.
+pub fn list_projects(search: &ProjectSearch) -> Vec<ProjectListing> {
+    list_projects_with(search, &resolve_target_dir)
+}
+
+/// Like [`list_projects`], with the target directory lookup passed in rather
+/// than read from the environment and cargo's config.
+pub fn list_projects_with(
+    search: &ProjectSearch,
+    target_dir: &dyn Fn(&Path) -> PathBuf,
+) -> Vec<ProjectListing> {
+    let mut projects = search.known_projects();
+    if !projects.iter().all(DiscoveredProject::is_fresh) {
+        projects = ProjectIndex::rebuild(search).projects;
+    }
+    projects
+        .iter()
+        .map(|project| ProjectListing::from_project(project, target_dir))
+        .collect()
+}
+
+/// Lays the listings out as a table with a header row, for `--list`.
+pub fn format_table(listings: &[ProjectListing]) -> String {
+    if listings.is_empty() {
+        return "No projects found".into();
+    }
+
+    let home = home_dir();
+    let rows = listings
+        .iter()
+        .map(|listing| {
+            [
+                listing.name.clone(),
+                display_path(&listing.path, home.as_deref()),
+                listing.binary.clone().unwrap_or_else(|| "-".into()),
+                if listing.release_binary.is_some() {
+                    "yes".into()
+                } else {
+                    "no".into()
+                },
+                listing
+                    .last_build
+                    .map(format_age)
+                    .unwrap_or_else(|| "-".into()),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let headers = ["NAME", "PATH", "BINARY", "RELEASE", "LAST BUILD"];
+    let widths = headers.map(str::len);
+    let widths = rows.iter().fold(widths, |mut widths, row| {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+        widths
+    });
+
+    let header = headers
+        .iter()
+        .zip(widths)
+        .map(|(header, width)| format!("{:width$}", header))
+        .collect::<Vec<_>>()
+        .join("  ");
+    let mut lines = vec![header.trim_end().bold().to_string()];
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{:width$}", cell))
+            .collect::<Vec<_>>()
+            .join("  ");
+        lines.push(line.trim_end().to_owned());
+    }
+    lines.join("\n")
+}
+
+fn display_path(path: &Path, home: Option<&Path>) -> String {
+    match home.and_then(|home| path.strip_prefix(home).ok()) {
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Formats a Unix timestamp as a coarse age relative to now, e.g. `3h ago`.
+pub fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default();
+    let seconds = now.saturating_sub(timestamp);
+
+    match seconds {
+        0..60 => "just now".into(),
+        60..3_600 => format!("{}m ago", seconds / 60),
+        3_600..86_400 => format!("{}h ago", seconds / 3_600),
+        _ => format!("{}d ago", seconds / 86_400),
+    }
+}
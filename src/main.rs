@@ -1,106 +1,136 @@
-mod cli;
-mod constants;
-mod helpers;
+use serde::Serialize;
+use std::error::Error;
+use std::path::Path;
 
-use anyhow::Result;
-use std::path::{Path, PathBuf};
-use std::process::Command;
+use rustr::cli::*;
+use rustr::config::{Config, project_search};
+use rustr::constants::*;
+use rustr::error::{Result, RustrError};
+use rustr::flows::{FlowContext, plan_flow, run_flow};
+use rustr::helpers::{banner, check_self_run, help, locate_project};
+use rustr::index::ProjectIndex;
+use rustr::list::{format_table, list_projects};
+use rustr::project::Project;
+use rustr::runner::SystemRunner;
+use rustr::toolchain::Toolchain;
 
-use crate::cli::*;
-use crate::constants::*;
-use crate::helpers::{
-    check_self_run, copy_bin, find_project_dir, get_binary_name, get_package_name, print_banner,
-    print_help, run_cargo_command,
-};
-
-fn main() -> Result<()> {
+fn main() {
     // Exit early if no arguments are provided
     if std::env::args_os().len() == 1 {
-        print_help();
-        return Ok(());
+        println!("{}", help());
+        return;
     }
 
     // Parse arguments
-    let args = parse_args()?;
+    let args = match parse_args() {
+        Ok(ParseOutcome::Run(args)) => args,
+        Ok(ParseOutcome::Help) => {
+            println!("{}", help());
+            return;
+        }
+        Ok(ParseOutcome::Version) => {
+            println!("{}", banner());
+            return;
+        }
+        Err(error) => exit_with_error(error),
+    };
 
-    // Check for self-run
-    check_self_run(args.project.as_deref(), args.project_name.as_deref())?;
+    if let Err(error) = run(args) {
+        exit_with_error(error);
+    }
+}
 
-    // Determine which project to use (--project flag takes precedence)
-    let project_name = if let Some(project) = args.project {
-        project
-    } else if let Some(project_name) = args.project_name {
-        project_name
-    } else if Path::new(CARGO_TOML).exists() {
-        // Get project name from current directory's Cargo.toml
-        get_package_name(&PathBuf::from("."))?
-    } else {
-        print_help();
-        return Ok(());
-    };
+/// Reports `error` and exits with the code for its class.
+fn exit_with_error(error: RustrError) -> ! {
+    // Problems with the project are reported under the banner, usage errors aren't
+    if !matches!(error, RustrError::Usage(_)) {
+        println!("{}", banner());
+    }
+    eprintln!("Error: {}", error);
+    if let Some(source) = error.source() {
+        eprintln!("\nCaused by:\n    {}", source);
+    }
 
-    // If any of our specific flags are set, do that action and exit
+    std::process::exit(error.exit_code());
+}
 
-    if args.test {
-        print_banner();
-        let project_dir = find_project_dir(&project_name)?;
-        run_cargo_command(&project_dir, &["test"])?;
-        println!("Test complete");
-        return Ok(());
+/// Pretty-prints `value` for `--json`.
+fn to_json(value: &impl Serialize) -> String {
+    // Listings and plans are plain data, so serializing them can't fail
+    serde_json::to_string_pretty(value).expect("output serializes to JSON")
+}
+
+fn run(args: CliArgs) -> Result<()> {
+    // Resolve where to look for projects
+    let config = Config::load()?;
+    let search = project_search(&config)?;
+
+    // Rebuild the project index on request; without a project there's nothing else to do
+    if args.reindex {
+        let index = ProjectIndex::build(&search);
+        if let Some(index_file) = &search.index_file {
+            index.save(index_file)?;
+        }
+        if !args.list {
+            println!("Indexed {} projects", index.projects.len());
+            if args.project.is_none() && args.project_name.is_none() {
+                return Ok(());
+            }
+        }
     }
 
-    if args.build {
-        print_banner();
-        let project_dir = find_project_dir(&project_name)?;
-        let binary_name = get_binary_name(&project_dir)?;
-        println!("Building project: {}", binary_name);
-        run_cargo_command(&project_dir, &["build"])?;
-        println!("Build complete");
+    // List discoverable projects and exit
+    if args.list {
+        let listings = list_projects(&search);
+        if args.json {
+            println!("{}", to_json(&listings));
+        } else {
+            println!("{}", banner());
+            println!("{}", format_table(&listings));
+        }
         return Ok(());
     }
 
-    if args.release {
-        print_banner();
-        let project_dir = find_project_dir(&project_name)?;
-        let binary_name = get_binary_name(&project_dir)?;
-        println!("Building release version of project: {}", binary_name);
-        run_cargo_command(&project_dir, &["build", "--release"])?;
-        println!("Release build complete");
+    // Check for self-run
+    check_self_run(args.project.as_deref(), args.project_name.as_deref())?;
+
+    // Determine which project to use (--project flag takes precedence)
+    let project = if let Some(project_name) = args.project.as_ref().or(args.project_name.as_ref()) {
+        locate_project(project_name, &search)?
+    } else if Path::new(CARGO_TOML).exists() {
+        // Use the package in the current directory
+        Project::resolve(Path::new("."), None)?
+    } else {
+        println!("{}", help());
         return Ok(());
-    }
+    };
+    let project_config = config.project(&project.package_name);
+    let toolchain = Toolchain::resolve(args.toolchain.as_deref(), project.cargo_dir());
+    let context = FlowContext {
+        args: &args,
+        project: &project,
+        project_config: &project_config,
+        toolchain: &toolchain,
+    };
 
-    if let Some(dest) = args.release_bin {
-        print_banner();
-        let project_dir = find_project_dir(&project_name)?;
-        let binary_name = get_binary_name(&project_dir)?;
-        println!("Building release version of project: {}", binary_name);
-        run_cargo_command(&project_dir, &["build", "--release"])?;
-        println!(
-            "Copying {} to {}",
-            binary_name,
-            dest.as_deref().unwrap_or(BIN_DIR)
-        );
-        copy_bin(&project_dir, &binary_name, dest.as_deref())?;
-        println!("Done");
+    // Show what would happen instead of doing it
+    if args.dry_run {
+        let plan = plan_flow(context)?;
+        if args.json {
+            println!("{}", to_json(&plan));
+        } else {
+            println!("{}", banner());
+            println!("{}", plan);
+        }
         return Ok(());
     }
 
-    // If we get here, we're running the target project
-    let project_dir = find_project_dir(&project_name)?;
-    let binary_name = get_binary_name(&project_dir)?;
-
-    // Build in release mode
-    run_cargo_command(&project_dir, &["build", "--release"])?;
-
-    // Run the app with all remaining arguments
-    let binary_path = project_dir
-        .join(TARGET_DIR)
-        .join(RELEASE_DIR)
-        .join(format!("{}{}", binary_name, BINARY_EXTENSION));
-    let status = Command::new(binary_path)
-        .args(&args.project_args)
-        .status()?;
+    let mut runner = SystemRunner::new(config.grace_period(), |line| println!("{}", line));
+    let code = run_flow(context, &mut runner, &mut |line| println!("{}", line))?;
 
     // Pass through the application's exit code
-    std::process::exit(status.code().unwrap_or(1));
+    if code != 0 {
+        std::process::exit(code);
+    }
+    Ok(())
 }
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+
+/// The parts of a `Cargo.toml` that rustr needs to resolve projects and binaries.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Manifest {
+    pub package: Option<Package>,
+    #[serde(default)]
+    pub bin: Vec<BinTarget>,
+    #[serde(default)]
+    pub example: Vec<BinTarget>,
+    pub workspace: Option<Workspace>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Package {
+    pub name: Option<String>,
+    pub default_run: Option<String>,
+    pub autobins: Option<bool>,
+    pub autoexamples: Option<bool>,
+}
+
+/// A `[[bin]]` or `[[example]]` target table.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct BinTarget {
+    pub name: Option<String>,
+    pub path: Option<String>,
+    #[serde(default)]
+    pub required_features: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Workspace {
+    pub members: Vec<String>,
+    pub exclude: Vec<String>,
+    pub default_members: Vec<String>,
+}
+
+impl Manifest {
+    pub fn load(project_dir: &Path) -> Result<Self> {
+        let cargo_toml = project_dir.join(CARGO_TOML);
+        let contents = fs::read_to_string(&cargo_toml).map_err(|error| {
+            RustrError::io(format!("Could not read {}", cargo_toml.display()), error)
+        })?;
+        Self::parse(&contents).map_err(|error| RustrError::ManifestParse {
+            path: cargo_toml,
+            message: error.to_string(),
+        })
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    pub fn package_name(&self) -> Option<&str> {
+        self.package.as_ref()?.name.as_deref()
+    }
+
+    pub fn default_run(&self) -> Option<&str> {
+        self.package.as_ref()?.default_run.as_deref()
+    }
+
+    pub fn autobins(&self) -> bool {
+        self.package
+            .as_ref()
+            .and_then(|package| package.autobins)
+            .unwrap_or(true)
+    }
+
+    pub fn autoexamples(&self) -> bool {
+        self.package
+            .as_ref()
+            .and_then(|package| package.autoexamples)
+            .unwrap_or(true)
+    }
+}
@@ -0,0 +1,359 @@
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
+use std::time::Duration;
+
+#[cfg(unix)]
+use std::sync::Arc;
+#[cfg(unix)]
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+#[cfg(unix)]
+use std::thread::{self, JoinHandle};
+#[cfg(unix)]
+use std::time::Instant;
+
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+
+/// Why a cargo command didn't succeed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CargoFailure {
+    /// cargo couldn't be found.
+    NotFound,
+    /// The build (or compiling the tests) failed.
+    BuildFailed { code: Option<i32> },
+    /// The tests were built and ran, but some failed.
+    TestsFailed { code: Option<i32> },
+    /// cargo was killed by a signal rustr didn't send.
+    Signaled { signal: i32 },
+    /// rustr forwarded a signal to cargo and it stopped.
+    Interrupted { signal: i32 },
+}
+
+/// A failed cargo command, with enough context to report it and to pick
+/// rustr's exit code.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CargoError {
+    pub command_line: String,
+    pub working_dir: PathBuf,
+    pub failure: CargoFailure,
+}
+
+impl CargoError {
+    pub fn new<S: AsRef<OsStr>>(working_dir: &Path, args: &[S], failure: CargoFailure) -> Self {
+        let command_line = std::iter::once(OsStr::new(CARGO_COMMAND))
+            .chain(args.iter().map(AsRef::as_ref))
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ");
+        Self {
+            command_line,
+            working_dir: working_dir.to_path_buf(),
+            failure,
+        }
+    }
+
+    /// The exit code rustr reports this failure with.
+    pub fn exit_code(&self) -> i32 {
+        match self.failure {
+            CargoFailure::NotFound => EXIT_COMMAND_NOT_FOUND,
+            CargoFailure::BuildFailed { .. } => EXIT_BUILD_FAILED,
+            CargoFailure::TestsFailed { .. } => EXIT_TESTS_FAILED,
+            CargoFailure::Signaled { signal } | CargoFailure::Interrupted { signal } => {
+                EXIT_SIGNAL_BASE + signal
+            }
+        }
+    }
+}
+
+impl fmt::Display for CargoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let command = &self.command_line;
+        match &self.failure {
+            CargoFailure::NotFound => write!(f, "Could not run '{}': not found", CARGO_COMMAND)?,
+            CargoFailure::BuildFailed { code } => {
+                write!(f, "Build failed: '{}' {}", command, describe_code(*code))?
+            }
+            CargoFailure::TestsFailed { code } => {
+                write!(f, "Tests failed: '{}' {}", command, describe_code(*code))?
+            }
+            CargoFailure::Signaled { signal } => {
+                write!(f, "'{}' was killed by {}", command, signal_name(*signal))?
+            }
+            CargoFailure::Interrupted { signal } => write!(
+                f,
+                "'{}' was interrupted by {}",
+                command,
+                signal_name(*signal)
+            )?,
+        }
+        write!(f, " (in {})", self.working_dir.display())
+    }
+}
+
+impl std::error::Error for CargoError {}
+
+fn describe_code(code: Option<i32>) -> String {
+    match code {
+        Some(code) => format!("exited with code {}", code),
+        None => "exited without a code".into(),
+    }
+}
+
+/// How a cargo command ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandStatus {
+    Success,
+    /// A non-zero exit, with the code when there is one.
+    Failed {
+        code: Option<i32>,
+    },
+    /// Killed by a signal rustr didn't send.
+    Signaled {
+        signal: i32,
+    },
+    /// Stopped after rustr forwarded a signal to it.
+    Interrupted {
+        signal: i32,
+    },
+}
+
+impl CommandStatus {
+    /// The failure this status amounts to, if any. `failure` builds the error
+    /// for a plain non-zero exit.
+    pub fn failure(self, failure: fn(Option<i32>) -> CargoFailure) -> Option<CargoFailure> {
+        match self {
+            CommandStatus::Success => None,
+            CommandStatus::Failed { code } => Some(failure(code)),
+            CommandStatus::Signaled { signal } => Some(CargoFailure::Signaled { signal }),
+            CommandStatus::Interrupted { signal } => Some(CargoFailure::Interrupted { signal }),
+        }
+    }
+}
+
+/// Classifies how a cargo command ended. `interrupted_by` is the signal rustr
+/// forwarded to it, if any.
+pub fn classify_exit(status: ExitStatus, interrupted_by: Option<i32>) -> CommandStatus {
+    if let Some(signal) = interrupted_by {
+        return CommandStatus::Interrupted { signal };
+    }
+    if status.success() {
+        return CommandStatus::Success;
+    }
+    if status.code().is_none() {
+        let signal = exit_code(status) - EXIT_SIGNAL_BASE;
+        if signal > 0 {
+            return CommandStatus::Signaled { signal };
+        }
+    }
+    CommandStatus::Failed {
+        code: status.code(),
+    }
+}
+
+pub fn signal_name(signal: i32) -> String {
+    match signal {
+        1 => "SIGHUP".into(),
+        2 => "SIGINT".into(),
+        9 => "SIGKILL".into(),
+        15 => "SIGTERM".into(),
+        other => format!("signal {}", other),
+    }
+}
+
+/// Runs the built binary with `args` and returns the exit code rustr should
+/// exit with.
+///
+/// On Unix the rustr process is replaced by the binary, so signals and job
+/// control go straight to it and this only returns if the exec fails.
+pub fn run_binary(binary_path: &Path, args: &[String]) -> Result<i32> {
+    let mut command = Command::new(binary_path);
+    command.args(args);
+
+    let could_not_run =
+        |error| RustrError::io(format!("Could not run {}", binary_path.display()), error);
+
+    // Anything still buffered would be lost once the process is replaced
+    io::stdout().flush().map_err(could_not_run)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(could_not_run(command.exec()))
+    }
+
+    #[cfg(not(unix))]
+    {
+        let status = command.status().map_err(could_not_run)?;
+        Ok(exit_code(status))
+    }
+}
+
+/// The exit code a shell would report for `status`: the process's own code,
+/// or 128 plus the signal number if a signal killed it.
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return EXIT_SIGNAL_BASE + signal;
+        }
+    }
+
+    EXIT_FAILURE
+}
+
+/// Spawns `command` in a process group of its own, so that it and everything
+/// it starts can be signalled together.
+pub fn spawn_in_own_group(command: &mut Command) -> io::Result<Child> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    command.spawn()
+}
+
+/// Kills a child started with [`spawn_in_own_group`], along with everything
+/// in its group, and reaps it.
+pub fn kill_own_group(child: &mut Child) {
+    #[cfg(unix)]
+    // SAFETY: signals only the group the child leads
+    unsafe {
+        libc::kill(-(child.id() as i32), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+#[cfg(unix)]
+const FORWARDED_SIGNALS: [libc::c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGHUP];
+#[cfg(unix)]
+static CHILD_GROUP: AtomicI32 = AtomicI32::new(0);
+#[cfg(unix)]
+static RECEIVED_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+#[cfg(unix)]
+extern "C" fn forward_signal(signal: libc::c_int) {
+    let group = CHILD_GROUP.load(Ordering::SeqCst);
+    if group > 0 {
+        // SAFETY: kill is async-signal-safe
+        unsafe { libc::kill(-group, signal) };
+    }
+    let _ = RECEIVED_SIGNAL.compare_exchange(0, signal, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+/// Forwards SIGINT, SIGTERM and SIGHUP to a child's process group while it
+/// runs. If the group is still around `grace_period` after the first signal,
+/// it is killed.
+pub struct SignalForwarder {
+    #[cfg(unix)]
+    previous: Vec<(libc::c_int, libc::sigaction)>,
+    #[cfg(unix)]
+    done: Arc<AtomicBool>,
+    #[cfg(unix)]
+    watcher: Option<JoinHandle<()>>,
+}
+
+impl SignalForwarder {
+    /// Starts forwarding to the group of `child`, which must have been started
+    /// with [`spawn_in_own_group`].
+    #[cfg(unix)]
+    pub fn start(child: &Child, grace_period: Duration) -> Self {
+        let group = child.id() as i32;
+        CHILD_GROUP.store(group, Ordering::SeqCst);
+        RECEIVED_SIGNAL.store(0, Ordering::SeqCst);
+
+        let previous = FORWARDED_SIGNALS
+            .iter()
+            .map(|&signal| {
+                // SAFETY: the handler only touches atomics and calls kill
+                unsafe {
+                    let mut action: libc::sigaction = std::mem::zeroed();
+                    action.sa_sigaction = forward_signal as *const () as libc::sighandler_t;
+                    action.sa_flags = libc::SA_RESTART;
+                    libc::sigemptyset(&mut action.sa_mask);
+                    let mut previous: libc::sigaction = std::mem::zeroed();
+                    libc::sigaction(signal, &action, &mut previous);
+                    (signal, previous)
+                }
+            })
+            .collect();
+
+        let done = Arc::new(AtomicBool::new(false));
+        let watcher = {
+            let done = Arc::clone(&done);
+            thread::spawn(move || watch_grace_period(group, grace_period, &done))
+        };
+
+        Self {
+            previous,
+            done,
+            watcher: Some(watcher),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn start(_child: &Child, _grace_period: Duration) -> Self {
+        Self {}
+    }
+
+    /// Stops forwarding, once the child has exited, and returns the first
+    /// signal that was forwarded to it.
+    #[cfg(unix)]
+    pub fn finish(mut self) -> Option<i32> {
+        self.stop();
+        match RECEIVED_SIGNAL.swap(0, Ordering::SeqCst) {
+            0 => None,
+            signal => Some(signal),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn finish(self) -> Option<i32> {
+        None
+    }
+
+    #[cfg(unix)]
+    fn stop(&mut self) {
+        self.done.store(true, Ordering::SeqCst);
+        if let Some(watcher) = self.watcher.take() {
+            let _ = watcher.join();
+        }
+        for (signal, previous) in self.previous.drain(..) {
+            // SAFETY: restores the action that was in place before start
+            unsafe { libc::sigaction(signal, &previous, std::ptr::null_mut()) };
+        }
+        CHILD_GROUP.store(0, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SignalForwarder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(unix)]
+fn watch_grace_period(group: i32, grace_period: Duration, done: &AtomicBool) {
+    let mut interrupted_at = None;
+    while !done.load(Ordering::SeqCst) {
+        if RECEIVED_SIGNAL.load(Ordering::SeqCst) != 0 {
+            let since = *interrupted_at.get_or_insert_with(Instant::now);
+            if since.elapsed() >= grace_period {
+                // SAFETY: signals the group this forwarder was started for
+                unsafe { libc::kill(-group, libc::SIGKILL) };
+                return;
+            }
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
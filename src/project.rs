@@ -0,0 +1,186 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::artifacts::resolve_target_dir;
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+use crate::manifest::{Manifest, Workspace};
+
+/// A package resolved for building, along with the workspace it belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Project {
+    pub dir: PathBuf,
+    pub package_name: String,
+    pub workspace_root: Option<PathBuf>,
+}
+
+impl Project {
+    /// Resolves the package to build from a project directory.
+    ///
+    /// A workspace root resolves to the member named `requested`, then to its
+    /// own root package, then to its only default or regular member.
+    pub fn resolve(dir: &Path, requested: Option<&str>) -> Result<Self> {
+        let manifest = Manifest::load(dir)?;
+
+        if let Some(workspace) = &manifest.workspace {
+            return resolve_workspace_package(dir, &manifest, workspace, requested);
+        }
+
+        let package_name = manifest
+            .package_name()
+            .ok_or_else(|| RustrError::MissingPackageName {
+                path: dir.join(CARGO_TOML),
+            })?
+            .to_owned();
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            package_name,
+            workspace_root: find_workspace_root(dir),
+        })
+    }
+
+    /// The directory cargo is invoked from.
+    pub fn cargo_dir(&self) -> &Path {
+        self.workspace_root.as_deref().unwrap_or(&self.dir)
+    }
+
+    /// The target directory this package's artifacts are written to.
+    pub fn target_dir(&self) -> PathBuf {
+        resolve_target_dir(self.cargo_dir())
+    }
+
+    /// Cargo arguments for this package, selecting it with `-p` inside a workspace.
+    pub fn cargo_args(&self, args: &[&str]) -> Vec<OsString> {
+        let mut cargo_args = args.iter().map(OsString::from).collect::<Vec<_>>();
+        if self.workspace_root.is_some() {
+            cargo_args.push("-p".into());
+            cargo_args.push(self.package_name.clone().into());
+        }
+        cargo_args
+    }
+}
+
+fn resolve_workspace_package(
+    root: &Path,
+    manifest: &Manifest,
+    workspace: &Workspace,
+    requested: Option<&str>,
+) -> Result<Project> {
+    let members = workspace_members(root, workspace)
+        .into_iter()
+        .filter_map(|dir| {
+            let name = Manifest::load(&dir).ok()?.package_name()?.to_owned();
+            Some((dir, name))
+        })
+        .collect::<Vec<_>>();
+
+    let member_project = |(dir, package_name): &(PathBuf, String)| Project {
+        dir: dir.clone(),
+        package_name: package_name.clone(),
+        workspace_root: Some(root.to_path_buf()),
+    };
+
+    if let Some(requested) = requested
+        && let Some(member) = members.iter().find(|(_, name)| name == requested)
+    {
+        return Ok(member_project(member));
+    }
+
+    if let Some(package_name) = manifest.package_name() {
+        return Ok(Project {
+            dir: root.to_path_buf(),
+            package_name: package_name.to_owned(),
+            workspace_root: Some(root.to_path_buf()),
+        });
+    }
+
+    let default_members = expand_member_globs(root, &workspace.default_members);
+    let defaults = members
+        .iter()
+        .filter(|(dir, _)| default_members.contains(dir))
+        .collect::<Vec<_>>();
+    if let [member] = defaults.as_slice() {
+        return Ok(member_project(member));
+    }
+
+    if let [member] = members.as_slice() {
+        return Ok(member_project(member));
+    }
+
+    Err(RustrError::AmbiguousPackage {
+        workspace: root.to_path_buf(),
+        packages: members.into_iter().map(|(_, name)| name).collect(),
+    })
+}
+
+/// Expands `members` and drops anything under an `exclude` entry, keeping only
+/// directories with a manifest.
+pub fn workspace_members(root: &Path, workspace: &Workspace) -> Vec<PathBuf> {
+    let excluded = workspace
+        .exclude
+        .iter()
+        .map(|path| root.join(path))
+        .collect::<Vec<_>>();
+
+    expand_member_globs(root, &workspace.members)
+        .into_iter()
+        .filter(|dir| !excluded.iter().any(|excluded| dir.starts_with(excluded)))
+        .filter(|dir| dir.join(CARGO_TOML).is_file())
+        .collect()
+}
+
+fn expand_member_globs(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+
+    let Some(root_str) = root.to_str() else {
+        return dirs;
+    };
+    let escaped_root = PathBuf::from(glob::Pattern::escape(root_str));
+
+    for pattern in patterns {
+        let full_pattern = escaped_root.join(pattern);
+        let Some(full_pattern) = full_pattern.to_str() else {
+            continue;
+        };
+        let Ok(paths) = glob::glob(full_pattern) else {
+            continue;
+        };
+        for path in paths.filter_map(|path| path.ok()) {
+            if path.is_dir() && !dirs.contains(&path) {
+                dirs.push(path);
+            }
+        }
+    }
+
+    dirs
+}
+
+/// Finds the workspace `dir` belongs to by walking up its ancestors, the way
+/// cargo does. The package must be listed as a member of the first workspace
+/// found; otherwise it's treated as standalone.
+pub fn find_workspace_root(dir: &Path) -> Option<PathBuf> {
+    let dir = fs::canonicalize(dir).ok()?;
+
+    for ancestor in dir.ancestors() {
+        if !ancestor.join(CARGO_TOML).is_file() {
+            continue;
+        }
+        let Ok(manifest) = Manifest::load(ancestor) else {
+            continue;
+        };
+        let Some(workspace) = &manifest.workspace else {
+            continue;
+        };
+
+        let is_member = ancestor == dir
+            || workspace_members(ancestor, workspace)
+                .iter()
+                .filter_map(|member| fs::canonicalize(member).ok())
+                .any(|member| member == dir);
+        return is_member.then(|| ancestor.to_path_buf());
+    }
+
+    None
+}
@@ -0,0 +1,271 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+use crate::artifacts::{Executable, parse_build_message};
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+use crate::helpers::copy_bin;
+use crate::process::{
+    CargoError, CargoFailure, CommandStatus, SignalForwarder, classify_exit, kill_own_group,
+    run_binary, spawn_in_own_group,
+};
+
+/// Runs the commands rustr needs: cargo, the binary it built, and copying that
+/// binary into place.
+pub trait CommandRunner {
+    /// Runs cargo from `dir` with its output going straight to the terminal.
+    fn cargo(&mut self, dir: &Path, args: &[OsString]) -> Result<CommandStatus>;
+
+    /// Runs cargo from `dir` with `--message-format` set to JSON, returning the
+    /// executables its build messages report.
+    fn cargo_build(
+        &mut self,
+        dir: &Path,
+        args: &[OsString],
+    ) -> Result<(CommandStatus, Vec<Executable>)>;
+
+    /// Runs a built binary and returns the exit code rustr should exit with.
+    fn binary(&mut self, path: &Path, args: &[String]) -> Result<i32>;
+
+    /// Copies a built binary into `dest_dir` and returns the path of the copy.
+    fn copy(&mut self, source: &Path, dest_dir: &Path) -> Result<PathBuf>;
+}
+
+/// Runs commands for real. cargo runs in its own process group, with
+/// termination signals forwarded to it and the group killed if it outlives
+/// `grace_period` after one. Lines a build writes to stdout that aren't cargo
+/// messages go to `passthrough`.
+#[derive(Clone, Debug)]
+pub struct SystemRunner {
+    grace_period: Duration,
+    passthrough: fn(&str),
+}
+
+impl SystemRunner {
+    pub fn new(grace_period: Duration, passthrough: fn(&str)) -> Self {
+        Self {
+            grace_period,
+            passthrough,
+        }
+    }
+
+    /// Spawns cargo and starts forwarding signals to it straight away, so an
+    /// interrupt during the build reaches cargo instead of orphaning it.
+    fn start_cargo(
+        &self,
+        dir: &Path,
+        args: &[OsString],
+        stdout: Stdio,
+    ) -> Result<(Child, SignalForwarder)> {
+        let mut command = Command::new(CARGO_COMMAND);
+        command.current_dir(dir).args(args).stdout(stdout);
+
+        let child = spawn_in_own_group(&mut command).map_err(|error| {
+            if error.kind() == ErrorKind::NotFound {
+                CargoError::new(dir, args, CargoFailure::NotFound).into()
+            } else {
+                RustrError::io(format!("Could not run {}", CARGO_COMMAND), error)
+            }
+        })?;
+        let forwarder = SignalForwarder::start(&child, self.grace_period);
+        Ok((child, forwarder))
+    }
+
+    fn wait_for_cargo(
+        &self,
+        mut child: Child,
+        forwarder: SignalForwarder,
+    ) -> Result<CommandStatus> {
+        let status = child.wait().map_err(|error| {
+            RustrError::io(format!("Could not wait for {}", CARGO_COMMAND), error)
+        })?;
+        Ok(classify_exit(status, forwarder.finish()))
+    }
+}
+
+impl CommandRunner for SystemRunner {
+    fn cargo(&mut self, dir: &Path, args: &[OsString]) -> Result<CommandStatus> {
+        let (child, forwarder) = self.start_cargo(dir, args, Stdio::inherit())?;
+        self.wait_for_cargo(child, forwarder)
+    }
+
+    fn cargo_build(
+        &mut self,
+        dir: &Path,
+        args: &[OsString],
+    ) -> Result<(CommandStatus, Vec<Executable>)> {
+        let (mut child, forwarder) = self.start_cargo(dir, args, Stdio::piped())?;
+
+        let executables = match read_build_messages(&mut child, self.passthrough) {
+            Ok(executables) => executables,
+            Err(error) => {
+                // Don't leave cargo building with nobody reading its output
+                kill_own_group(&mut child);
+                return Err(RustrError::io("Could not read cargo's build output", error));
+            }
+        };
+
+        let status = self.wait_for_cargo(child, forwarder)?;
+        Ok((status, executables))
+    }
+
+    fn binary(&mut self, path: &Path, args: &[String]) -> Result<i32> {
+        run_binary(path, args)
+    }
+
+    fn copy(&mut self, source: &Path, dest_dir: &Path) -> Result<PathBuf> {
+        copy_bin(source, dest_dir)
+    }
+}
+
+/// Reads the executables from cargo's JSON build messages on the child's
+/// stdout. Diagnostics are still rendered to stderr as usual.
+fn read_build_messages(child: &mut Child, passthrough: fn(&str)) -> io::Result<Vec<Executable>> {
+    let mut executables = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            let line = line?;
+            match parse_build_message(&line) {
+                Some(message) => executables.extend(message),
+                // Not a cargo message, so pass it through untouched
+                None => passthrough(&line),
+            }
+        }
+    }
+    Ok(executables)
+}
+
+/// A command a [`RecordingRunner`] was asked to run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "action", rename_all = "kebab-case")]
+pub enum RecordedCommand {
+    Cargo {
+        dir: PathBuf,
+        args: Vec<String>,
+    },
+    #[serde(rename = "run")]
+    Binary {
+        path: PathBuf,
+        args: Vec<String>,
+    },
+    Copy {
+        source: PathBuf,
+        destination: PathBuf,
+    },
+}
+
+impl RecordedCommand {
+    /// The command as it would be typed, e.g. `cargo build --release`.
+    pub fn command_line(&self) -> String {
+        let (program, args) = match self {
+            RecordedCommand::Cargo { args, .. } => (CARGO_COMMAND.to_owned(), args.clone()),
+            RecordedCommand::Binary { path, args } => (path.display().to_string(), args.clone()),
+            RecordedCommand::Copy {
+                source,
+                destination,
+            } => (
+                "cp".to_owned(),
+                vec![
+                    source.display().to_string(),
+                    destination.display().to_string(),
+                ],
+            ),
+        };
+        std::iter::once(program)
+            .chain(args)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Records commands instead of running them, answering with canned results.
+/// cargo succeeds unless told otherwise, builds report no executables, and
+/// nothing is copied.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingRunner {
+    pub commands: Vec<RecordedCommand>,
+    cargo_statuses: VecDeque<CommandStatus>,
+    executables: Vec<Executable>,
+    binary_exit_code: i32,
+}
+
+impl RecordingRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues the status the next unanswered cargo command ends with.
+    pub fn with_cargo_status(mut self, status: CommandStatus) -> Self {
+        self.cargo_statuses.push_back(status);
+        self
+    }
+
+    /// Sets the executables every build reports.
+    pub fn with_executables(mut self, executables: Vec<Executable>) -> Self {
+        self.executables = executables;
+        self
+    }
+
+    pub fn with_binary_exit_code(mut self, code: i32) -> Self {
+        self.binary_exit_code = code;
+        self
+    }
+
+    /// The recorded commands as they would be typed.
+    pub fn command_lines(&self) -> Vec<String> {
+        self.commands
+            .iter()
+            .map(RecordedCommand::command_line)
+            .collect()
+    }
+
+    fn record_cargo(&mut self, dir: &Path, args: &[OsString]) -> CommandStatus {
+        self.commands.push(RecordedCommand::Cargo {
+            dir: dir.to_path_buf(),
+            args: args
+                .iter()
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect(),
+        });
+        self.cargo_statuses
+            .pop_front()
+            .unwrap_or(CommandStatus::Success)
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn cargo(&mut self, dir: &Path, args: &[OsString]) -> Result<CommandStatus> {
+        Ok(self.record_cargo(dir, args))
+    }
+
+    fn cargo_build(
+        &mut self,
+        dir: &Path,
+        args: &[OsString],
+    ) -> Result<(CommandStatus, Vec<Executable>)> {
+        let status = self.record_cargo(dir, args);
+        Ok((status, self.executables.clone()))
+    }
+
+    fn binary(&mut self, path: &Path, args: &[String]) -> Result<i32> {
+        self.commands.push(RecordedCommand::Binary {
+            path: path.to_path_buf(),
+            args: args.to_vec(),
+        });
+        Ok(self.binary_exit_code)
+    }
+
+    fn copy(&mut self, source: &Path, dest_dir: &Path) -> Result<PathBuf> {
+        let destination = dest_dir.join(source.file_name().unwrap_or(source.as_os_str()));
+        self.commands.push(RecordedCommand::Copy {
+            source: source.to_path_buf(),
+            destination: destination.clone(),
+        });
+        Ok(destination)
+    }
+}
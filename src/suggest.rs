@@ -0,0 +1,64 @@
+use crate::constants::*;
+
+/// Ranks `candidates` by how closely they resemble `name` and returns the best
+/// few. Prefix matches rank first, then substring matches, then names within
+/// a small edit distance.
+pub fn closest_matches<'a, I>(name: &str, candidates: I) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let name = name.to_lowercase();
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut ranked = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let lowered = candidate.to_lowercase();
+            let distance = edit_distance(&name, &lowered);
+            let rank = if lowered.starts_with(&name) {
+                0
+            } else if lowered.contains(&name) {
+                1
+            } else if distance <= max_distance {
+                2
+            } else {
+                return None;
+            };
+            Some((rank, distance, candidate))
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort();
+    ranked.dedup_by(|a, b| a.2 == b.2);
+    ranked
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, _, candidate)| candidate)
+        .collect()
+}
+
+/// Formats suggestions as a trailing hint for an error message.
+pub fn did_you_mean(suggestions: &[&str]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+    format!("\nDid you mean: {}?", suggestions.join(", "))
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
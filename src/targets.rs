@@ -0,0 +1,203 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::*;
+use crate::error::{Result, RustrError};
+use crate::manifest::{BinTarget, Manifest};
+use crate::suggest::closest_matches;
+
+/// The kinds of runnable targets rustr can select.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetKind {
+    Bin,
+    Example,
+}
+
+impl TargetKind {
+    /// The target kind as cargo reports it in build messages.
+    pub fn cargo_kind(self) -> &'static str {
+        match self {
+            TargetKind::Bin => BIN_KIND,
+            TargetKind::Example => EXAMPLE_KIND,
+        }
+    }
+
+    pub(crate) fn describe(self) -> (&'static str, &'static str) {
+        match self {
+            TargetKind::Bin => ("binary", "binaries"),
+            TargetKind::Example => ("example", "examples"),
+        }
+    }
+}
+
+/// A runnable target declared in the manifest or inferred from the layout.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Target {
+    pub kind: TargetKind,
+    pub name: String,
+    /// Source path relative to the package directory, when known.
+    pub path: Option<PathBuf>,
+    /// Features that must be enabled for cargo to build this target.
+    pub required_features: Vec<String>,
+}
+
+impl Target {
+    /// The `--features` arguments that enable this target's required features.
+    pub fn required_feature_args(&self) -> Vec<String> {
+        if self.required_features.is_empty() {
+            return Vec::new();
+        }
+        vec!["--features".into(), self.required_features.join(",")]
+    }
+}
+
+impl fmt::Display for Target {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if !self.required_features.is_empty() {
+            write!(
+                f,
+                " (enabling required features: {})",
+                self.required_features.join(", ")
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Lists the targets of `kind` in a package: explicit `[[bin]]`/`[[example]]`
+/// tables first, then the ones cargo infers from `src/main.rs`, `src/bin/` or
+/// `examples/`, unless an explicit target already claims the name or path.
+pub fn discover_targets(project_dir: &Path, manifest: &Manifest, kind: TargetKind) -> Vec<Target> {
+    let (explicit, autodiscover) = match kind {
+        TargetKind::Bin => (&manifest.bin, manifest.autobins()),
+        TargetKind::Example => (&manifest.example, manifest.autoexamples()),
+    };
+
+    let mut targets = explicit
+        .iter()
+        .filter_map(|target| explicit_target(kind, target))
+        .collect::<Vec<_>>();
+
+    if !autodiscover {
+        return targets;
+    }
+
+    for (name, path) in inferred_targets(project_dir, manifest, kind) {
+        let claimed = targets.iter().any(|target| {
+            target.name == name || target.path.as_deref().is_some_and(|p| same_path(p, &path))
+        });
+        if !claimed {
+            targets.push(Target {
+                kind,
+                name,
+                path: Some(path),
+                required_features: Vec::new(),
+            });
+        }
+    }
+
+    targets
+}
+
+fn explicit_target(kind: TargetKind, target: &BinTarget) -> Option<Target> {
+    Some(Target {
+        kind,
+        name: target.name.clone()?,
+        path: target.path.as_deref().map(PathBuf::from),
+        required_features: target.required_features.clone(),
+    })
+}
+
+fn inferred_targets(
+    project_dir: &Path,
+    manifest: &Manifest,
+    kind: TargetKind,
+) -> Vec<(String, PathBuf)> {
+    let mut targets = Vec::new();
+
+    let dir = match kind {
+        TargetKind::Bin => {
+            let main = Path::new(SRC_DIR).join(MAIN_SOURCE_FILE);
+            if let Some(package_name) = manifest.package_name()
+                && project_dir.join(&main).is_file()
+            {
+                targets.push((package_name.to_owned(), main));
+            }
+            Path::new(SRC_DIR).join(BIN_SOURCE_DIR)
+        }
+        TargetKind::Example => PathBuf::from(EXAMPLES_DIR),
+    };
+
+    let Ok(entries) = fs::read_dir(project_dir.join(&dir)) else {
+        return targets;
+    };
+    let mut entries = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+    entries.sort();
+
+    for entry in entries {
+        let Some(name) = entry.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        if entry.is_file() && entry.extension().is_some_and(|ext| ext == "rs") {
+            targets.push((name.to_owned(), dir.join(format!("{}.rs", name))));
+        } else if entry.join(MAIN_SOURCE_FILE).is_file() {
+            targets.push((name.to_owned(), dir.join(name).join(MAIN_SOURCE_FILE)));
+        }
+    }
+
+    targets
+}
+
+fn same_path(declared: &Path, inferred: &Path) -> bool {
+    declared.strip_prefix(".").unwrap_or(declared) == inferred
+}
+
+/// Checks that the package has a target of `kind` named `name`, listing the
+/// available ones (and the closest matches) when it doesn't.
+pub fn select_target(project_dir: &Path, kind: TargetKind, name: &str) -> Result<Target> {
+    let manifest = Manifest::load(project_dir)?;
+    let targets = discover_targets(project_dir, &manifest, kind);
+
+    if let Some(target) = targets.iter().find(|target| target.name == name) {
+        return Ok(target.clone());
+    }
+
+    let available = targets
+        .into_iter()
+        .map(|target| target.name)
+        .collect::<Vec<_>>();
+    let suggestions = closest_matches(name, available.iter().map(String::as_str))
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+    Err(RustrError::TargetNotFound {
+        kind,
+        name: name.to_owned(),
+        dir: project_dir.to_path_buf(),
+        available,
+        suggestions,
+    })
+}
+
+/// Finds the target of `kind` named `name`, falling back to a bare target for
+/// names cargo will resolve on its own.
+pub fn find_target(project_dir: &Path, kind: TargetKind, name: &str) -> Target {
+    Manifest::load(project_dir)
+        .ok()
+        .and_then(|manifest| {
+            discover_targets(project_dir, &manifest, kind)
+                .into_iter()
+                .find(|target| target.name == name)
+        })
+        .unwrap_or_else(|| Target {
+            kind,
+            name: name.to_owned(),
+            path: None,
+            required_features: Vec::new(),
+        })
+}
@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::constants::*;
+
+/// Where the toolchain rustup will pick came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ToolchainSource {
+    /// A leading `+toolchain` argument.
+    Argument,
+    /// The `RUSTUP_TOOLCHAIN` environment variable.
+    Environment,
+    /// A `rust-toolchain.toml` or `rust-toolchain` file.
+    File(PathBuf),
+    /// Nothing overrides rustup's default toolchain.
+    Default,
+}
+
+/// The toolchain cargo will run with, resolved the way rustup does.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Toolchain {
+    pub name: Option<String>,
+    pub source: ToolchainSource,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolchainFile {
+    toolchain: ToolchainSection,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolchainSection {
+    channel: Option<String>,
+    path: Option<String>,
+}
+
+impl Toolchain {
+    /// Resolves the toolchain for cargo invoked from `cargo_dir`.
+    pub fn resolve(explicit: Option<&str>, cargo_dir: &Path) -> Self {
+        let env_toolchain = env::var(TOOLCHAIN_ENV).ok();
+        Self::resolve_from(explicit, env_toolchain, cargo_dir)
+    }
+
+    /// Resolves the toolchain in rustup's order: a `+toolchain` argument, then
+    /// `RUSTUP_TOOLCHAIN`, then the closest toolchain file above `cargo_dir`.
+    pub fn resolve_from(
+        explicit: Option<&str>,
+        env_toolchain: Option<String>,
+        cargo_dir: &Path,
+    ) -> Self {
+        if let Some(name) = explicit {
+            return Self {
+                name: Some(name.to_owned()),
+                source: ToolchainSource::Argument,
+            };
+        }
+
+        if let Some(name) = env_toolchain.filter(|name| !name.is_empty()) {
+            return Self {
+                name: Some(name),
+                source: ToolchainSource::Environment,
+            };
+        }
+
+        let absolute_dir = fs::canonicalize(cargo_dir).unwrap_or_else(|_| cargo_dir.to_path_buf());
+        for dir in absolute_dir.ancestors() {
+            // rustup prefers the legacy file when both exist
+            for file in TOOLCHAIN_FILES {
+                let path = dir.join(file);
+                if let Some(name) = read_toolchain_file(&path) {
+                    return Self {
+                        name: Some(name),
+                        source: ToolchainSource::File(path),
+                    };
+                }
+            }
+        }
+
+        Self {
+            name: None,
+            source: ToolchainSource::Default,
+        }
+    }
+
+    /// The `+toolchain` argument to forward to cargo, if one was given.
+    pub fn cargo_arg(&self) -> Option<String> {
+        match (&self.source, &self.name) {
+            (ToolchainSource::Argument, Some(name)) => Some(format!("+{}", name)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Toolchain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(name) = &self.name else {
+            return write!(f, "default");
+        };
+        match &self.source {
+            ToolchainSource::Argument => write!(f, "{} (from +{})", name, name),
+            ToolchainSource::Environment => write!(f, "{} (from {})", name, TOOLCHAIN_ENV),
+            ToolchainSource::File(path) => write!(f, "{} (from {})", name, path.display()),
+            ToolchainSource::Default => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Reads the toolchain name from a toolchain file. The legacy `rust-toolchain`
+/// file may hold either the TOML format or just a channel name.
+fn read_toolchain_file(path: &Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+
+    if let Ok(file) = toml::from_str::<ToolchainFile>(&contents) {
+        return file.toolchain.channel.or(file.toolchain.path);
+    }
+
+    let channel = contents.trim();
+    (!channel.is_empty() && !channel.contains(char::is_whitespace)).then(|| channel.to_owned())
+}
@@ -0,0 +1,194 @@
+mod common;
+
+use common::TempDir;
+use rustr::artifacts::{
+    Executable, binary_extension, binary_path, example_path, find_executable, parse_build_message,
+    profile_args, profile_command, profile_dir_name, resolve_target_dir_from,
+};
+use rustr::constants::BINARY_EXTENSION;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn target_dir_defaults_to_target_in_cargo_dir() {
+    let dir = TempDir::new();
+    fs::create_dir_all(dir.path.join("app")).unwrap();
+
+    let target_dir = resolve_target_dir_from(&dir.path.join("app"), None, None);
+    assert_eq!(target_dir, dir.path.join("app/target"));
+}
+
+#[test]
+fn target_dir_env_override_wins() {
+    let dir = TempDir::new();
+    dir.write_file("app/.cargo/config.toml", "[build]\ntarget-dir = \"out\"\n");
+
+    let absolute = resolve_target_dir_from(
+        &dir.path.join("app"),
+        Some(PathBuf::from("/ssd/cache")),
+        None,
+    );
+    assert_eq!(absolute, PathBuf::from("/ssd/cache"));
+
+    let relative =
+        resolve_target_dir_from(&dir.path.join("app"), Some(PathBuf::from("build")), None);
+    assert_eq!(relative, dir.path.join("app/build"));
+}
+
+#[test]
+fn target_dir_from_closest_cargo_config() {
+    let dir = TempDir::new();
+    dir.write_file(".cargo/config.toml", "[build]\ntarget-dir = \"outer\"\n");
+    dir.write_file("ws/.cargo/config.toml", "[build]\ntarget-dir = \"inner\"\n");
+    fs::create_dir_all(dir.path.join("ws/app")).unwrap();
+    fs::create_dir_all(dir.path.join("other")).unwrap();
+
+    let inner = resolve_target_dir_from(&dir.path.join("ws/app"), None, None);
+    assert_eq!(inner, dir.path.join("ws/inner"));
+
+    let outer = resolve_target_dir_from(&dir.path.join("other"), None, None);
+    assert_eq!(outer, dir.path.join("outer"));
+}
+
+#[test]
+fn target_dir_from_legacy_config_and_cargo_home() {
+    let dir = TempDir::new();
+    dir.write_file(
+        "legacy/.cargo/config",
+        "[build]\ntarget-dir = \"/abs/target\"\n",
+    );
+    dir.write_file(
+        "home/.cargo/config.toml",
+        "[build]\ntarget-dir = \"shared-target\"\n",
+    );
+    fs::create_dir_all(dir.path.join("plain")).unwrap();
+
+    let legacy = resolve_target_dir_from(&dir.path.join("legacy"), None, None);
+    assert_eq!(legacy, PathBuf::from("/abs/target"));
+
+    let cargo_home = dir.path.join("home/.cargo");
+    let from_home = resolve_target_dir_from(&dir.path.join("plain"), None, Some(&cargo_home));
+    assert_eq!(from_home, dir.path.join("home/shared-target"));
+}
+
+#[test]
+fn cargo_config_without_target_dir_is_ignored() {
+    let dir = TempDir::new();
+    dir.write_file("app/.cargo/config.toml", "[alias]\nb = \"build\"\n");
+
+    let target_dir = resolve_target_dir_from(&dir.path.join("app"), None, None);
+    assert_eq!(target_dir, dir.path.join("app/target"));
+}
+
+#[test]
+fn profile_dirs_follow_cargo_layout() {
+    assert_eq!(profile_dir_name("dev"), "debug");
+    assert_eq!(profile_dir_name("test"), "debug");
+    assert_eq!(profile_dir_name("release"), "release");
+    assert_eq!(profile_dir_name("bench"), "release");
+    assert_eq!(profile_dir_name("dist"), "dist");
+}
+
+#[test]
+fn profile_args_use_cargo_shorthand_for_builtin_profiles() {
+    assert!(profile_args("dev").is_empty());
+    assert_eq!(profile_args("release"), vec!["--release"]);
+    assert_eq!(profile_args("dist"), vec!["--profile", "dist"]);
+
+    assert_eq!(profile_command("test", None), vec!["test"]);
+    assert_eq!(
+        profile_command("build", Some("profiling")),
+        vec!["build", "--profile", "profiling"]
+    );
+}
+
+#[test]
+fn binary_path_uses_profile_dir() {
+    let path = binary_path(Path::new("/t"), None, "dist", "app");
+    assert_eq!(
+        path,
+        PathBuf::from(format!("/t/dist/app{BINARY_EXTENSION}"))
+    );
+}
+
+#[test]
+fn build_message_reports_executable_for_bin_artifact() {
+    let line = r#"{"reason":"compiler-artifact","package_id":"app 0.1.0","target":{"kind":["bin"],"crate_types":["bin"],"name":"app","src_path":"/src/main.rs"},"filenames":["/out/app"],"executable":"/out/app","fresh":true}"#;
+
+    let executable = parse_build_message(line).unwrap().unwrap();
+    assert_eq!(executable.target_name, "app");
+    assert_eq!(executable.kinds, vec!["bin".to_string()]);
+    assert_eq!(executable.path, PathBuf::from("/out/app"));
+}
+
+#[test]
+fn build_message_without_executable_is_skipped() {
+    let library = r#"{"reason":"compiler-artifact","target":{"kind":["lib"],"name":"app"},"executable":null}"#;
+    let finished = r#"{"reason":"build-finished","success":true}"#;
+
+    assert_eq!(parse_build_message(library), Some(None));
+    assert_eq!(parse_build_message(finished), Some(None));
+}
+
+#[test]
+fn non_json_line_is_not_a_build_message() {
+    assert_eq!(parse_build_message("   Compiling app v0.1.0"), None);
+}
+
+#[test]
+fn find_executable_picks_last_target_of_kind_with_matching_name() {
+    let executable = |name: &str, kind: &str, path: &str| Executable {
+        target_name: name.into(),
+        kinds: vec![kind.into()],
+        path: PathBuf::from(path),
+    };
+    let executables = [
+        executable("app", "example", "/out/examples/app"),
+        executable("app", "bin", "/out/old/app"),
+        executable("tool", "bin", "/out/tool"),
+        executable("app", "bin", "/out/app"),
+    ];
+
+    assert_eq!(
+        find_executable(&executables, "bin", "app"),
+        Some(Path::new("/out/app"))
+    );
+    assert_eq!(
+        find_executable(&executables, "example", "app"),
+        Some(Path::new("/out/examples/app"))
+    );
+    assert_eq!(find_executable(&executables, "bin", "missing"), None);
+}
+
+#[test]
+fn cross_compiled_artifacts_live_under_the_triple() {
+    let path = binary_path(
+        Path::new("/t"),
+        Some("x86_64-unknown-linux-musl"),
+        "release",
+        "app",
+    );
+    assert_eq!(
+        path,
+        PathBuf::from("/t/x86_64-unknown-linux-musl/release/app")
+    );
+
+    let path = example_path(
+        Path::new("/t"),
+        Some("x86_64-pc-windows-gnu"),
+        "dev",
+        "demo",
+    );
+    assert_eq!(
+        path,
+        PathBuf::from("/t/x86_64-pc-windows-gnu/debug/examples/demo.exe")
+    );
+}
+
+#[test]
+fn binary_extension_follows_the_target_triple() {
+    assert_eq!(binary_extension(None), BINARY_EXTENSION);
+    assert_eq!(binary_extension(Some("x86_64-pc-windows-msvc")), ".exe");
+    assert_eq!(binary_extension(Some("wasm32-wasip1")), ".wasm");
+    assert_eq!(binary_extension(Some("aarch64-apple-darwin")), "");
+}
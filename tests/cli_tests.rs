@@ -1,19 +1,40 @@
-use rustr::cli::{CliArgs, parse_args_from};
+use rustr::cli::{Action, CliArgs, ParseOutcome, parse_args_from};
+use rustr::error::RustrError;
+use rustr::features::FeatureArgs;
 
 fn v(args: &[&str]) -> Vec<String> {
     args.iter().map(|s| s.to_string()).collect()
 }
 
+fn parse(args: Vec<String>) -> Result<CliArgs, RustrError> {
+    parse_args_from(args).map(|outcome| match outcome {
+        ParseOutcome::Run(args) => args,
+        other => panic!("expected arguments to run with, got {other:?}"),
+    })
+}
+
 #[test]
 fn no_arguments() {
-    let cfg = parse_args_from::<_, String>(Vec::<String>::new()).unwrap();
+    let cfg = parse(Vec::new()).unwrap();
     assert_eq!(
         cfg,
         CliArgs {
+            toolchain: None,
             test: false,
             build: false,
             release: false,
             release_bin: None,
+            debug_run: false,
+            dry_run: false,
+            reindex: false,
+            list: false,
+            json: false,
+            bin: None,
+            example: None,
+            features: FeatureArgs::default(),
+            profile: None,
+            target_triple: None,
+            extra_cargo_args: vec![],
             project: None,
             project_name: None,
             project_args: vec![],
@@ -23,14 +44,14 @@ fn no_arguments() {
 
 #[test]
 fn positional_project_then_args() {
-    let cfg = parse_args_from(v(&["myproj", "--foo", "bar"])).unwrap();
+    let cfg = parse(v(&["myproj", "--foo", "bar"])).unwrap();
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(cfg.project_args, vec![String::from("--foo"), "bar".into()]);
 }
 
 #[test]
 fn project_flag_anywhere() {
-    let cfg = parse_args_from(v(&["--release", "foo", "--project=myproj", "--verbose"])).unwrap();
+    let cfg = parse(v(&["--release", "foo", "--project=myproj", "--verbose"])).unwrap();
 
     assert!(cfg.release);
     assert_eq!(cfg.project, Some("myproj".into()));
@@ -43,35 +64,78 @@ fn project_flag_anywhere() {
 #[test]
 fn release_bin_space_and_equals() {
     // space form
-    let cfg = parse_args_from(v(&["--release-bin", "/opt/bin", "myproj", "--flag"])).unwrap();
+    let cfg = parse(v(&["--release-bin", "/opt/bin", "myproj", "--flag"])).unwrap();
     assert_eq!(cfg.release_bin, Some(Some("/opt/bin".into())));
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(cfg.project_args, vec![String::from("--flag")]);
 
     // equals form
-    let cfg_eq = parse_args_from(v(&["myproj", "--release-bin=/custom"])).unwrap();
+    let cfg_eq = parse(v(&["myproj", "--release-bin=/custom"])).unwrap();
     assert_eq!(cfg_eq.release_bin, Some(Some("/custom".into())));
     assert_eq!(cfg_eq.project_name, Some("myproj".into()));
 }
 
 #[test]
 fn release_bin_no_dest_defaults() {
-    let cfg = parse_args_from(v(&["myproj", "--release-bin"])).unwrap();
+    let cfg = parse(v(&["myproj", "--release-bin"])).unwrap();
     assert_eq!(cfg.release_bin, Some(None));
     assert_eq!(cfg.project_name, Some("myproj".into()));
 }
 
+#[test]
+fn release_bin_does_not_take_a_flag_as_dest() {
+    let cfg = parse(v(&["myproj", "--release-bin", "-n"])).unwrap();
+    assert_eq!(cfg.release_bin, Some(None));
+    assert!(cfg.dry_run);
+
+    let cfg = parse(v(&["myproj", "-n", "--release-bin"])).unwrap();
+    assert_eq!(cfg.release_bin, Some(None));
+    assert!(cfg.dry_run);
+    assert!(cfg.project_args.is_empty());
+}
+
 #[test]
 fn build_and_release_flags_any_order() {
-    let cfg = parse_args_from(v(&["--build", "myproj", "--release"])).unwrap();
+    let cfg = parse(v(&["--build", "myproj", "--release"])).unwrap();
     assert!(cfg.build);
     assert!(cfg.release);
     assert_eq!(cfg.project_name, Some("myproj".into()));
 }
 
+#[test]
+fn reindex_flag() {
+    let cfg = parse(v(&["--reindex", "myproj"])).unwrap();
+    assert!(cfg.reindex);
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+}
+
+#[test]
+fn list_and_json_flags() {
+    let cfg = parse(v(&["--list", "--json"])).unwrap();
+    assert!(cfg.list);
+    assert!(cfg.json);
+    assert_eq!(cfg.project_name, None);
+}
+
+#[test]
+fn json_flag_goes_to_the_program_without_list_or_dry_run() {
+    let cfg = parse(v(&["mytool", "--json", "report"])).unwrap();
+    assert!(!cfg.json);
+    assert_eq!(cfg.project_name, Some("mytool".into()));
+    assert_eq!(cfg.project_args, v(&["--json", "report"]));
+
+    let cfg = parse(v(&["mytool", "--json", "--dry-run"])).unwrap();
+    assert!(cfg.json);
+    assert!(cfg.project_args.is_empty());
+
+    let cfg = parse(v(&["mytool", "--dry-run", "--", "--json"])).unwrap();
+    assert!(!cfg.json);
+    assert_eq!(cfg.project_args, v(&["--json"]));
+}
+
 #[test]
 fn missing_project_value_error() {
-    let err = parse_args_from::<_, String>(v(&["--project"])).unwrap_err();
+    let err = parse(v(&["--project"])).unwrap_err();
     assert!(
         err.to_string().contains("Missing project name"),
         "unexpected error text: {err}"
@@ -80,7 +144,7 @@ fn missing_project_value_error() {
 
 #[test]
 fn unknown_long_option_pass_through() {
-    let cfg = parse_args_from(v(&["myproj", "--unknown-flag", "-vv"])).unwrap();
+    let cfg = parse(v(&["myproj", "--unknown-flag", "-vv"])).unwrap();
     assert_eq!(
         cfg.project_args,
         vec![String::from("--unknown-flag"), String::from("-vv")]
@@ -90,7 +154,7 @@ fn unknown_long_option_pass_through() {
 #[test]
 fn dash_dash_as_argument_separator() {
     // Test that "--" acts as an explicit argument separator
-    let cfg = parse_args_from(v(&["myproj", "--", "--build", "-h"])).unwrap();
+    let cfg = parse(v(&["myproj", "--", "--build", "-h"])).unwrap();
     assert_eq!(cfg.project_name, Some("myproj".into()));
     // "--build" and "-h" should be treated as project args, not flags
     assert!(!cfg.build);
@@ -103,7 +167,7 @@ fn dash_dash_as_argument_separator() {
 #[test]
 fn interleaved_flags_and_args() {
     // Test complex interleaving of flags and args
-    let cfg = parse_args_from(v(&["--test", "arg1", "--project=myproj", "arg2"])).unwrap();
+    let cfg = parse(v(&["--test", "arg1", "--project=myproj", "arg2"])).unwrap();
     assert!(cfg.test);
     assert_eq!(cfg.project, Some("myproj".into()));
     assert_eq!(
@@ -115,14 +179,14 @@ fn interleaved_flags_and_args() {
 #[test]
 fn empty_arg_list() {
     // Test with empty arg list but with an = sign
-    let cfg = parse_args_from(v(&["--project="])).unwrap_err();
+    let cfg = parse(v(&["--project="])).unwrap_err();
     assert!(cfg.to_string().contains("Missing project name"));
 }
 
 #[test]
 fn single_dash_options() {
     // Test that single dash options other than -h and -V are passed through
-    let cfg = parse_args_from(v(&["myproj", "-x", "-foo"])).unwrap();
+    let cfg = parse(v(&["myproj", "-x", "-foo"])).unwrap();
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(
         cfg.project_args,
@@ -133,7 +197,7 @@ fn single_dash_options() {
 #[test]
 fn project_with_hyphen() {
     // Test that a project name with a hyphen is handled correctly
-    let cfg = parse_args_from(v(&["my-project", "--build"])).unwrap();
+    let cfg = parse(v(&["my-project", "--build"])).unwrap();
     assert!(cfg.build);
     assert_eq!(cfg.project_name, Some("my-project".into()));
 }
@@ -141,8 +205,7 @@ fn project_with_hyphen() {
 #[test]
 fn multiple_flag_formats() {
     // Test multiple flag formats in same command
-    let cfg =
-        parse_args_from(v(&["--test", "--project", "myproj", "--release-bin=/path"])).unwrap();
+    let cfg = parse(v(&["--test", "--project", "myproj", "--release-bin=/path"])).unwrap();
 
     assert!(cfg.test);
     assert_eq!(cfg.project, Some("myproj".into()));
@@ -152,8 +215,8 @@ fn multiple_flag_formats() {
 #[test]
 fn ordering_of_flags() {
     // Test that the order of flags doesn't affect precedence
-    let cfg1 = parse_args_from(v(&["myproj", "--release", "--build"])).unwrap();
-    let cfg2 = parse_args_from(v(&["myproj", "--build", "--release"])).unwrap();
+    let cfg1 = parse(v(&["myproj", "--release", "--build"])).unwrap();
+    let cfg2 = parse(v(&["myproj", "--build", "--release"])).unwrap();
 
     // Both should have build=true, release=true
     assert!(cfg1.build && cfg1.release);
@@ -162,7 +225,7 @@ fn ordering_of_flags() {
 
 #[test]
 fn flags_after_project_name() {
-    let cfg = parse_args_from(v(&["myproj", "--build", "--", "--some-arg"])).unwrap();
+    let cfg = parse(v(&["myproj", "--build", "--", "--some-arg"])).unwrap();
     assert!(cfg.build);
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(cfg.project_args, vec![String::from("--some-arg")]);
@@ -171,7 +234,7 @@ fn flags_after_project_name() {
 #[test]
 fn whitespace_in_values() {
     // Test handling of whitespace in option values
-    let cfg = parse_args_from(v(&[
+    let cfg = parse(v(&[
         "--project",
         "my project",
         "--release-bin",
@@ -187,7 +250,7 @@ fn whitespace_in_values() {
 fn same_option_multiple_times() {
     // Test what happens when an option is specified multiple times
     // Last one should win
-    let cfg = parse_args_from(v(&[
+    let cfg = parse(v(&[
         "--project=first",
         "--project=second",
         "--release-bin=/path1",
@@ -202,7 +265,7 @@ fn same_option_multiple_times() {
 #[test]
 fn special_characters_in_values() {
     // Test handling of special characters in option values
-    let cfg = parse_args_from(v(&[
+    let cfg = parse(v(&[
         "--project=proj#$%@!",
         "--release-bin=/path/with/!@#$%^&*()",
     ]))
@@ -215,7 +278,7 @@ fn special_characters_in_values() {
 #[test]
 fn unicode_characters() {
     // Test handling of Unicode characters
-    let cfg = parse_args_from(v(&["--project=项目名称", "--release-bin=/路径/到/二进制"])).unwrap();
+    let cfg = parse(v(&["--project=项目名称", "--release-bin=/路径/到/二进制"])).unwrap();
 
     assert_eq!(cfg.project, Some("项目名称".into()));
     assert_eq!(cfg.release_bin, Some(Some("/路径/到/二进制".into())));
@@ -223,22 +286,21 @@ fn unicode_characters() {
 
 #[test]
 fn multiple_dash_behavior() {
-    let cfg = parse_args_from(v(&["myproj", "---weird-flag"])).unwrap();
+    let cfg = parse(v(&["myproj", "---weird-flag"])).unwrap();
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(cfg.project_args, vec![String::from("---weird-flag")]);
 }
 
 #[test]
 fn escaped_characters() {
-    let cfg =
-        parse_args_from(v(&["--project=proj\\name", "--release-bin=/path\\to\\bin"])).unwrap();
+    let cfg = parse(v(&["--project=proj\\name", "--release-bin=/path\\to\\bin"])).unwrap();
     assert_eq!(cfg.project, Some("proj\\name".into()));
     assert_eq!(cfg.release_bin, Some(Some("/path\\to\\bin".into())));
 }
 
 #[test]
 fn empty_project_args() {
-    let cfg = parse_args_from(v(&["myproj", "", "--flag", ""])).unwrap();
+    let cfg = parse(v(&["myproj", "", "--flag", ""])).unwrap();
     assert_eq!(cfg.project_name, Some("myproj".into()));
     assert_eq!(
         cfg.project_args,
@@ -248,6 +310,247 @@ fn empty_project_args() {
 
 #[test]
 fn deep_project_path() {
-    let cfg = parse_args_from(v(&["--project=/very/deep/nested/path/project"])).unwrap();
+    let cfg = parse(v(&["--project=/very/deep/nested/path/project"])).unwrap();
     assert_eq!(cfg.project, Some("/very/deep/nested/path/project".into()));
 }
+
+#[test]
+fn bin_and_example_flags_take_names() {
+    let cfg = parse(v(&["--bin", "worker", "myproj", "--", "x"])).unwrap();
+    assert_eq!(cfg.bin, Some("worker".into()));
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+    assert_eq!(cfg.project_args, vec![String::from("x")]);
+    assert_eq!(
+        cfg.cargo_flags(),
+        vec![String::from("--bin"), "worker".into()]
+    );
+
+    let cfg = parse(v(&["--example=demo", "myproj"])).unwrap();
+    assert_eq!(cfg.example, Some("demo".into()));
+    assert_eq!(
+        cfg.cargo_flags(),
+        vec![String::from("--example"), "demo".into()]
+    );
+}
+
+#[test]
+fn bin_flag_requires_name() {
+    let err = parse(v(&["--bin"])).unwrap_err();
+    assert!(err.to_string().contains("Missing binary name after --bin"));
+
+    let err = parse(v(&["--example="])).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing example name after --example")
+    );
+}
+
+#[test]
+fn bin_and_example_conflict() {
+    let err = parse(v(&["--bin", "a", "--example", "b"])).unwrap_err();
+    assert!(err.to_string().contains("can't be used together"));
+}
+
+#[test]
+fn feature_flags_accumulate() {
+    let cfg = parse(v(&[
+        "--features",
+        "cli,tls",
+        "-F",
+        "tls json",
+        "--features=extra",
+        "--no-default-features",
+        "myproj",
+    ]))
+    .unwrap();
+
+    assert_eq!(cfg.features.features, vec!["cli", "tls", "json", "extra"]);
+    assert!(cfg.features.no_default_features);
+    assert!(!cfg.features.all_features);
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+}
+
+#[test]
+fn short_features_flag_after_release_bin() {
+    let cfg = parse(v(&["myproj", "--release-bin", "-F", "cli"])).unwrap();
+    assert_eq!(cfg.release_bin, Some(None));
+    assert_eq!(cfg.features.features, vec!["cli"]);
+    assert!(cfg.project_args.is_empty());
+}
+
+#[test]
+fn missing_features_value_error() {
+    let err = parse(v(&["--features"])).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing feature name after --features")
+    );
+}
+
+#[test]
+fn profile_flag_takes_name() {
+    let cfg = parse(v(&["--profile", "dist", "myproj", "--release-bin"])).unwrap();
+    assert_eq!(cfg.profile, Some("dist".into()));
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+    assert_eq!(cfg.release_bin, Some(None));
+
+    let err = parse(v(&["--profile="])).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing profile name after --profile")
+    );
+}
+
+#[test]
+fn target_flag_is_forwarded_to_cargo() {
+    let cfg = parse(v(&["--target=x86_64-unknown-linux-musl", "myproj"])).unwrap();
+    assert_eq!(cfg.target_triple, Some("x86_64-unknown-linux-musl".into()));
+    assert_eq!(
+        cfg.cargo_flags(),
+        vec![String::from("--target"), "x86_64-unknown-linux-musl".into()]
+    );
+}
+
+#[test]
+fn toolchain_before_project_name_is_recognised() {
+    let cfg = parse(v(&["+nightly", "myproj", "--test"])).unwrap();
+    assert_eq!(cfg.toolchain, Some("nightly".into()));
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+    assert!(cfg.test);
+
+    let cfg = parse(v(&["--test", "+nightly", "myproj"])).unwrap();
+    assert_eq!(cfg.toolchain, Some("nightly".into()));
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+    assert!(cfg.project_args.is_empty());
+
+    // After the project name, `+name` belongs to the program
+    let cfg = parse(v(&["myproj", "+nightly"])).unwrap();
+    assert_eq!(cfg.toolchain, None);
+    assert_eq!(cfg.project_args, vec![String::from("+nightly")]);
+
+    let cfg = parse(v(&["--project", "myproj", "+nightly"])).unwrap();
+    assert_eq!(cfg.toolchain, None);
+    assert_eq!(cfg.project_args, vec![String::from("+nightly")]);
+
+    let err = parse(v(&["+"])).unwrap_err();
+    assert!(err.to_string().contains("Missing toolchain name"));
+}
+
+#[test]
+fn cargo_args_are_kept_apart_from_program_args() {
+    let cfg = parse(v(&[
+        "--cargo-arg=--locked",
+        "myproj",
+        "--cargo-arg",
+        "-j4",
+        "--verbose",
+        "--",
+        "--cargo-arg=--offline",
+    ]))
+    .unwrap();
+
+    assert_eq!(cfg.extra_cargo_args, vec!["--locked", "-j4"]);
+    assert_eq!(cfg.cargo_flags(), vec!["--locked", "-j4"]);
+    assert_eq!(
+        cfg.project_args,
+        vec![String::from("--verbose"), "--cargo-arg=--offline".into()]
+    );
+
+    let err = parse(v(&["--cargo-arg="])).unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("Missing cargo argument after --cargo-arg")
+    );
+}
+
+#[test]
+fn debug_run_flag() {
+    let cfg = parse(v(&["myproj", "--debug-run", "--", "--debug-run"])).unwrap();
+    assert!(cfg.debug_run);
+    assert_eq!(cfg.project_args, vec![String::from("--debug-run")]);
+}
+
+#[test]
+fn help_and_version_are_parse_outcomes() {
+    assert_eq!(
+        parse_args_from(v(&["myproj", "--help", "--bogus"])).unwrap(),
+        ParseOutcome::Help
+    );
+    assert_eq!(parse_args_from(v(&["-V"])).unwrap(), ParseOutcome::Version);
+    assert!(matches!(
+        parse_args_from(v(&["myproj", "--", "-h"])).unwrap(),
+        ParseOutcome::Run(_)
+    ));
+}
+
+#[test]
+fn parse_errors_are_usage_errors() {
+    let error = parse(v(&["--bin"])).unwrap_err();
+    assert!(matches!(error, RustrError::Usage(_)));
+    assert_eq!(error.exit_code(), 2);
+}
+
+#[test]
+fn dry_run_flag_and_short_form() {
+    assert!(
+        parse(v(&["myproj", "--dry-run", "--release-bin"]))
+            .unwrap()
+            .dry_run
+    );
+
+    let cfg = parse(v(&["-n", "myproj", "--", "-n"])).unwrap();
+    assert!(cfg.dry_run);
+    assert_eq!(cfg.project_name, Some("myproj".into()));
+    assert_eq!(cfg.project_args, vec![String::from("-n")]);
+}
+
+#[test]
+fn actions_run_in_pipeline_order() {
+    let cfg = parse(v(&["myproj", "--release-bin=/opt", "--test"])).unwrap();
+    assert_eq!(
+        cfg.actions(),
+        vec![Action::Test, Action::ReleaseBin(Some("/opt".into()))]
+    );
+
+    let cfg = parse(v(&["myproj", "--release", "--build", "--test"])).unwrap();
+    assert_eq!(
+        cfg.actions(),
+        vec![Action::Test, Action::Build, Action::Release]
+    );
+
+    assert!(parse(v(&["myproj"])).unwrap().actions().is_empty());
+}
+
+#[test]
+fn conflicting_actions_are_rejected() {
+    let err = parse(v(&["myproj", "--release", "--release-bin"])).unwrap_err();
+    assert!(matches!(err, RustrError::Usage(_)));
+    assert!(
+        err.to_string()
+            .starts_with("--release and --release-bin can't be used together"),
+        "{err}"
+    );
+
+    let err = parse(v(&["myproj", "--build", "--release", "--profile=dist"])).unwrap_err();
+    assert!(err.to_string().contains("'dist' profile"), "{err}");
+
+    let err = parse(v(&["myproj", "--debug-run", "--test"])).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("--debug-run and --test can't be used together"),
+        "{err}"
+    );
+
+    let err = parse(v(&["myproj", "--debug-run", "--profile", "dist"])).unwrap_err();
+    assert!(
+        err.to_string()
+            .starts_with("--debug-run and --profile can't be used together"),
+        "{err}"
+    );
+
+    let err = parse(v(&["--list", "--build"])).unwrap_err();
+    assert_eq!(err.to_string(), "--list and --build can't be used together");
+
+    // Conflicting flags meant for the program are left alone
+    assert!(parse(v(&["myproj", "--release", "--", "--release-bin"])).is_ok());
+}
@@ -0,0 +1,58 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use rustr::constants::CARGO_TOML;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A directory under the system temp dir, removed again on drop.
+pub struct TempDir {
+    pub path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("rustr-tests-{unique}-{count}"));
+        fs::create_dir_all(&path).unwrap();
+        // Canonical, so it compares equal to the paths rustr resolves
+        Self {
+            path: fs::canonicalize(path).unwrap(),
+        }
+    }
+
+    /// A directory holding a project with this `Cargo.toml`.
+    pub fn with_manifest(cargo_toml: &str) -> Self {
+        let dir = Self::new();
+        dir.write_file(CARGO_TOML, cargo_toml);
+        dir
+    }
+
+    pub fn write_file(&self, relative_path: &str, contents: &str) {
+        let path = self.path.join(relative_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    /// Writes a minimal package called `name` and returns its directory.
+    pub fn write_package(&self, relative_dir: &str, name: &str) -> PathBuf {
+        self.write_file(
+            &format!("{relative_dir}/{CARGO_TOML}"),
+            &format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n"),
+        );
+        self.path.join(relative_dir)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
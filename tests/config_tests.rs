@@ -0,0 +1,131 @@
+mod common;
+
+use common::TempDir;
+use rustr::config::{Config, resolve_search_roots};
+use rustr::error::RustrError;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn search_roots_default_to_canonical_location() {
+    let roots = resolve_search_roots(None, &[], Path::new("/home/me"));
+    assert_eq!(roots, vec![PathBuf::from("/home/me/dev/Rust")]);
+}
+
+#[test]
+fn search_roots_env_entries_come_before_config_entries() {
+    let env_paths = std::env::join_paths(["/env/one", "/env/two"]).unwrap();
+    let roots = resolve_search_roots(
+        Some(env_paths),
+        &[String::from("/config/one")],
+        Path::new("/home/me"),
+    );
+
+    assert_eq!(
+        roots,
+        vec![
+            PathBuf::from("/env/one"),
+            PathBuf::from("/env/two"),
+            PathBuf::from("/config/one"),
+        ]
+    );
+}
+
+#[test]
+fn search_roots_expand_tilde_and_skip_duplicates() {
+    let roots = resolve_search_roots(
+        Some(OsString::from("~/src")),
+        &[String::from("~/src"), String::from("~/work/rust")],
+        Path::new("/home/me"),
+    );
+
+    assert_eq!(
+        roots,
+        vec![
+            PathBuf::from("/home/me/src"),
+            PathBuf::from("/home/me/work/rust"),
+        ]
+    );
+}
+
+#[test]
+fn search_roots_ignore_empty_env_entries() {
+    let roots = resolve_search_roots(Some(OsString::from("")), &[], Path::new("/home/me"));
+    assert_eq!(roots, vec![PathBuf::from("/home/me/dev/Rust")]);
+}
+
+#[test]
+fn config_parses_search_paths() {
+    let config = Config::parse(
+        r#"
+search-paths = ["~/src", "/opt/rust"]
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.search_paths,
+        vec![String::from("~/src"), String::from("/opt/rust")]
+    );
+}
+
+#[test]
+fn config_parses_search_depth() {
+    let config = Config::parse("search-depth = 5").unwrap();
+    assert_eq!(config.search_depth, Some(5));
+    assert_eq!(Config::default().search_depth, None);
+}
+
+#[test]
+fn config_parses_prefix_match() {
+    assert!(Config::parse("prefix-match = true").unwrap().prefix_match);
+    assert!(!Config::default().prefix_match);
+}
+
+#[test]
+fn config_rejects_unknown_keys() {
+    let error = Config::parse("search-path = [\"~/src\"]").unwrap_err();
+    assert!(error.to_string().contains("unknown field"), "{error}");
+}
+
+#[test]
+fn config_parses_per_project_feature_defaults() {
+    let config = Config::parse(
+        r#"
+[projects.billing-api]
+features = ["cli", "tls json"]
+no-default-features = true
+"#,
+    )
+    .unwrap();
+
+    let features = config.project("billing-api").feature_args();
+    assert_eq!(features.features, vec!["cli", "tls", "json"]);
+    assert!(features.no_default_features);
+    assert!(!features.all_features);
+
+    assert_eq!(config.project("other").feature_args(), Default::default());
+}
+
+#[test]
+fn config_parses_per_project_debug_run() {
+    let config = Config::parse("[projects.scratch]\ndebug-run = true\n").unwrap();
+    assert!(config.project("scratch").debug_run);
+    assert!(!config.project("other").debug_run);
+}
+
+#[test]
+fn config_file_errors_name_the_file() {
+    let dir = TempDir::new();
+    dir.write_file("config.toml", "search-depth = \"deep\"\n");
+    let path = dir.path.join("config.toml");
+
+    let error = Config::from_file(&path).unwrap_err();
+    assert!(matches!(error, RustrError::Config { .. }), "{error:?}");
+    assert!(
+        error
+            .to_string()
+            .starts_with(&format!("Invalid config file {}: ", path.display())),
+        "{error}"
+    );
+}
@@ -0,0 +1,96 @@
+use rustr::error::RustrError;
+use rustr::process::{CargoError, CargoFailure};
+use rustr::targets::TargetKind;
+use std::error::Error;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn exit_codes_follow_the_error_class() {
+    let cargo_failed =
+        |failure| RustrError::from(CargoError::new(Path::new("/work"), &["build"], failure));
+
+    assert_eq!(
+        RustrError::Usage("Missing binary name after --bin".into()).exit_code(),
+        2
+    );
+    assert_eq!(
+        cargo_failed(CargoFailure::BuildFailed { code: Some(101) }).exit_code(),
+        3
+    );
+    assert_eq!(
+        cargo_failed(CargoFailure::TestsFailed { code: Some(101) }).exit_code(),
+        4
+    );
+    assert_eq!(cargo_failed(CargoFailure::NotFound).exit_code(), 127);
+    assert_eq!(
+        RustrError::SelfRun {
+            name: "rustr".into()
+        }
+        .exit_code(),
+        1
+    );
+}
+
+#[test]
+fn messages_list_candidates_and_suggestions() {
+    let error = RustrError::ProjectNotFound {
+        name: "bil".into(),
+        roots: vec![PathBuf::from("/src"), PathBuf::from("/work")],
+        max_depth: 3,
+        suggestions: vec!["billing".into()],
+    };
+    assert_eq!(
+        error.to_string(),
+        "Project directory not found: bil\nSearched (depth 3):\n  /src\n  /work\nDid you mean: billing?"
+    );
+
+    let error = RustrError::AmbiguousBinary {
+        names: vec!["server".into(), "worker".into()],
+    };
+    assert!(error.to_string().ends_with("--bin:\n  server\n  worker"));
+
+    let error = RustrError::TargetNotFound {
+        kind: TargetKind::Example,
+        name: "demo".into(),
+        dir: PathBuf::from("/app"),
+        available: vec![],
+        suggestions: vec![],
+    };
+    assert_eq!(
+        error.to_string(),
+        "No example target named 'demo' in /app\nAvailable examples: (none)"
+    );
+}
+
+#[test]
+fn io_errors_keep_their_source() {
+    let error = RustrError::io(
+        "Could not read /app/Cargo.toml",
+        io::Error::new(io::ErrorKind::PermissionDenied, "permission denied"),
+    );
+    assert_eq!(error.to_string(), "Could not read /app/Cargo.toml");
+    assert_eq!(error.source().unwrap().to_string(), "permission denied");
+}
+
+#[test]
+fn config_and_index_errors_name_the_file() {
+    let error = RustrError::Config {
+        path: PathBuf::from("/home/me/.config/rustr/config.toml"),
+        message: "unknown field `search-path`".into(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Invalid config file /home/me/.config/rustr/config.toml: unknown field `search-path`"
+    );
+    assert_eq!(error.exit_code(), 1);
+
+    let error = RustrError::Index {
+        path: PathBuf::from("/home/me/.cache/rustr/index.json"),
+        message: "EOF while parsing a value".into(),
+    };
+    assert_eq!(
+        error.to_string(),
+        "Invalid project index /home/me/.cache/rustr/index.json: EOF while parsing a value"
+    );
+}
@@ -0,0 +1,34 @@
+use rustr::features::FeatureArgs;
+
+#[test]
+fn no_feature_flags_add_no_cargo_args() {
+    assert!(FeatureArgs::default().cargo_args().is_empty());
+}
+
+#[test]
+fn merged_flags_keep_defaults_and_add_cli_features() {
+    let defaults = FeatureArgs {
+        features: vec!["cli".into()],
+        no_default_features: true,
+        ..FeatureArgs::default()
+    };
+    let mut cli = FeatureArgs::default();
+    cli.add_features("cli,tls");
+
+    let merged = cli.merged_with(&defaults);
+    assert_eq!(
+        merged.cargo_args(),
+        vec!["--features", "cli,tls", "--no-default-features"]
+    );
+}
+
+#[test]
+fn all_features_from_either_side_wins() {
+    let cli = FeatureArgs {
+        all_features: true,
+        ..FeatureArgs::default()
+    };
+
+    let merged = cli.merged_with(&FeatureArgs::default());
+    assert_eq!(merged.cargo_args(), vec!["--all-features"]);
+}
@@ -0,0 +1,409 @@
+mod common;
+
+use common::TempDir;
+use rustr::artifacts::Executable;
+use rustr::cli::{CliArgs, ParseOutcome, parse_args_from};
+use rustr::config::ProjectConfig;
+use rustr::error::{Result, RustrError};
+use rustr::flows::{FlowContext, plan_flow, run_flow};
+use rustr::process::{CargoFailure, CommandStatus};
+use rustr::project::Project;
+use rustr::runner::{RecordedCommand, RecordingRunner};
+use rustr::toolchain::{Toolchain, ToolchainSource};
+use std::fs;
+use std::ops::Deref;
+use std::path::PathBuf;
+
+const CARGO_TOML_WITH_TOOL: &str = r#"
+[package]
+name = "app"
+
+[features]
+cli = []
+
+[[bin]]
+name = "tool"
+path = "src/bin/tool.rs"
+required-features = ["cli"]
+"#;
+
+/// A package with a main binary and a `tool` binary that needs the `cli`
+/// feature.
+struct TempProject(TempDir);
+
+impl TempProject {
+    fn new() -> Self {
+        let dir = TempDir::with_manifest(CARGO_TOML_WITH_TOOL);
+        dir.write_file("src/main.rs", "fn main() {}\n");
+        dir.write_file("src/bin/tool.rs", "fn main() {}\n");
+        Self(dir)
+    }
+
+    /// Runs the flow for `args` and returns its exit code and what it reported.
+    fn run(
+        &self,
+        args: &[&str],
+        project_config: &ProjectConfig,
+        runner: &mut RecordingRunner,
+    ) -> Result<(i32, Vec<String>)> {
+        let args = parse(args);
+        let project = Project::resolve(&self.path, None).unwrap();
+        let toolchain = Toolchain {
+            name: None,
+            source: ToolchainSource::Default,
+        };
+        let context = FlowContext {
+            args: &args,
+            project: &project,
+            project_config,
+            toolchain: &toolchain,
+        };
+
+        let mut reported = Vec::new();
+        let code = run_flow(context, runner, &mut |line| reported.push(line))?;
+        Ok((code, reported))
+    }
+
+    /// A file standing in for a binary cargo built.
+    fn built_binary(&self, name: &str) -> Executable {
+        let path = self.path.join("target/release").join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "binary").unwrap();
+        Executable {
+            target_name: name.into(),
+            kinds: vec!["bin".into()],
+            path,
+        }
+    }
+}
+
+impl Deref for TempProject {
+    type Target = TempDir;
+
+    fn deref(&self) -> &TempDir {
+        &self.0
+    }
+}
+
+fn parse(args: &[&str]) -> CliArgs {
+    match parse_args_from(args.iter().copied()).unwrap() {
+        ParseOutcome::Run(args) => args,
+        other => panic!("expected arguments to run with, got {other:?}"),
+    }
+}
+
+fn cargo_failure(error: RustrError) -> CargoFailure {
+    match error {
+        RustrError::CargoFailed(error) => error.failure,
+        other => panic!("expected a cargo failure, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_flow_builds_the_tests_before_running_them() {
+    let project = TempProject::new();
+    let mut runner = RecordingRunner::new();
+
+    let (code, reported) = project
+        .run(&["--test"], &ProjectConfig::default(), &mut runner)
+        .unwrap();
+
+    assert_eq!(code, 0);
+    assert_eq!(
+        runner.command_lines(),
+        vec!["cargo test --no-run", "cargo test"]
+    );
+    assert!(matches!(
+        &runner.commands[0],
+        RecordedCommand::Cargo { dir, .. } if *dir == project.path
+    ));
+    assert_eq!(reported[1], "Toolchain: default");
+    assert_eq!(reported.last().unwrap(), "Test complete");
+}
+
+#[test]
+fn test_flow_tells_compile_errors_from_failing_tests() {
+    let project = TempProject::new();
+
+    let mut runner =
+        RecordingRunner::new().with_cargo_status(CommandStatus::Failed { code: Some(101) });
+    let error = project
+        .run(&["--test"], &ProjectConfig::default(), &mut runner)
+        .unwrap_err();
+    assert_eq!(error.exit_code(), 3);
+    assert_eq!(runner.commands.len(), 1);
+
+    let mut runner = RecordingRunner::new()
+        .with_cargo_status(CommandStatus::Success)
+        .with_cargo_status(CommandStatus::Failed { code: Some(101) });
+    let error = project
+        .run(&["--test"], &ProjectConfig::default(), &mut runner)
+        .unwrap_err();
+    assert_eq!(
+        cargo_failure(error),
+        CargoFailure::TestsFailed { code: Some(101) }
+    );
+}
+
+#[test]
+fn build_flow_enables_required_and_configured_features() {
+    let project = TempProject::new();
+    let project_config = ProjectConfig {
+        features: vec!["extra".into()],
+        ..ProjectConfig::default()
+    };
+    let mut runner = RecordingRunner::new();
+
+    let (_, reported) = project
+        .run(&["--build", "--bin", "tool"], &project_config, &mut runner)
+        .unwrap();
+
+    assert_eq!(
+        runner.command_lines(),
+        vec!["cargo build --bin tool --features extra --features cli"]
+    );
+    assert!(reported.contains(&"Building project: tool (enabling required features: cli)".into()));
+    assert_eq!(reported.last().unwrap(), "Build complete");
+}
+
+#[test]
+fn release_flow_uses_the_release_or_given_profile() {
+    let project = TempProject::new();
+
+    let mut runner = RecordingRunner::new();
+    project
+        .run(&["--release"], &ProjectConfig::default(), &mut runner)
+        .unwrap();
+    assert_eq!(runner.command_lines(), vec!["cargo build --release"]);
+
+    let mut runner = RecordingRunner::new();
+    let (_, reported) = project
+        .run(
+            &["--release", "--profile", "dist"],
+            &ProjectConfig::default(),
+            &mut runner,
+        )
+        .unwrap();
+    assert_eq!(runner.command_lines(), vec!["cargo build --profile dist"]);
+    assert!(reported.contains(&"Building dist version of project: app".into()));
+}
+
+#[test]
+fn release_bin_flow_copies_the_built_binary() {
+    let project = TempProject::new();
+    let binary = project.built_binary("app");
+    let dest = project.path.join("installed");
+    let mut runner = RecordingRunner::new().with_executables(vec![binary.clone()]);
+
+    let (_, reported) = project
+        .run(
+            &["--release-bin", dest.to_str().unwrap()],
+            &ProjectConfig::default(),
+            &mut runner,
+        )
+        .unwrap();
+
+    assert_eq!(
+        runner.commands[1..],
+        [RecordedCommand::Copy {
+            source: binary.path,
+            destination: dest.join("app"),
+        }]
+    );
+    assert_eq!(
+        runner.command_lines()[0],
+        "cargo build --release --message-format=json-render-diagnostics"
+    );
+    assert_eq!(reported.last().unwrap(), "Done");
+}
+
+#[test]
+fn plan_lists_the_steps_without_running_them() {
+    let project = TempProject::new();
+    let args = parse(&["--release-bin", "/opt/tools", "--bin", "tool"]);
+    let resolved = Project::resolve(&project.path, None).unwrap();
+    let toolchain = Toolchain {
+        name: Some("nightly".into()),
+        source: ToolchainSource::Argument,
+    };
+    let context = FlowContext {
+        args: &args,
+        project: &resolved,
+        project_config: &ProjectConfig::default(),
+        toolchain: &toolchain,
+    };
+
+    let plan = plan_flow(context).unwrap();
+
+    assert_eq!(plan.package, "app");
+    assert_eq!(plan.cargo_dir, project.path);
+    assert_eq!(
+        plan.target.as_deref(),
+        Some("tool (enabling required features: cli)")
+    );
+    let source = resolved.target_dir().join("release/tool");
+    assert_eq!(
+        plan.steps,
+        vec![
+            RecordedCommand::Cargo {
+                dir: project.path.clone(),
+                args: vec![
+                    "+nightly".into(),
+                    "build".into(),
+                    "--release".into(),
+                    "--bin".into(),
+                    "tool".into(),
+                    "--features".into(),
+                    "cli".into(),
+                    "--message-format=json-render-diagnostics".into(),
+                ],
+            },
+            RecordedCommand::Copy {
+                source,
+                destination: PathBuf::from("/opt/tools/tool"),
+            },
+        ]
+    );
+    assert!(!PathBuf::from("/opt/tools").exists());
+
+    let text = plan.to_string();
+    assert!(
+        text.contains("Toolchain: nightly (from +nightly)"),
+        "{text}"
+    );
+    assert!(text.contains("  2. cp "), "{text}");
+
+    let json = serde_json::to_value(&plan).unwrap();
+    assert_eq!(json["steps"][0]["action"], "cargo");
+    assert_eq!(json["steps"][1]["action"], "copy");
+}
+
+#[test]
+fn plan_for_tests_has_no_target() {
+    let project = TempProject::new();
+    let args = parse(&["--test", "-n"]);
+    let resolved = Project::resolve(&project.path, None).unwrap();
+    let toolchain = Toolchain {
+        name: None,
+        source: ToolchainSource::Default,
+    };
+    let context = FlowContext {
+        args: &args,
+        project: &resolved,
+        project_config: &ProjectConfig::default(),
+        toolchain: &toolchain,
+    };
+
+    let plan = plan_flow(context).unwrap();
+    assert_eq!(plan.target, None);
+    assert_eq!(plan.steps.len(), 2);
+}
+
+#[test]
+fn actions_run_in_order_and_stop_at_the_first_failure() {
+    let project = TempProject::new();
+    let dest = project.path.join("installed");
+    let args = ["--release-bin", dest.to_str().unwrap(), "--test"];
+
+    let mut runner = RecordingRunner::new().with_executables(vec![project.built_binary("app")]);
+    let (_, reported) = project
+        .run(&args, &ProjectConfig::default(), &mut runner)
+        .unwrap();
+    assert_eq!(
+        runner.command_lines()[..3],
+        [
+            "cargo test --no-run",
+            "cargo test",
+            "cargo build --release --message-format=json-render-diagnostics",
+        ]
+    );
+    assert!(matches!(runner.commands[3], RecordedCommand::Copy { .. }));
+    assert!(reported.contains(&"Running --test, --release-bin".into()));
+
+    let mut runner = RecordingRunner::new()
+        .with_cargo_status(CommandStatus::Success)
+        .with_cargo_status(CommandStatus::Failed { code: Some(101) });
+    let error = project
+        .run(&args, &ProjectConfig::default(), &mut runner)
+        .unwrap_err();
+    assert_eq!(error.exit_code(), 4);
+    assert_eq!(
+        runner.command_lines(),
+        vec!["cargo test --no-run", "cargo test"]
+    );
+}
+
+#[test]
+fn plan_for_a_pipeline_names_the_installed_binary() {
+    let project = TempProject::new();
+    let args = parse(&["--test", "--release-bin", "/opt/tools"]);
+    let resolved = Project::resolve(&project.path, None).unwrap();
+    let toolchain = Toolchain {
+        name: None,
+        source: ToolchainSource::Default,
+    };
+    let context = FlowContext {
+        args: &args,
+        project: &resolved,
+        project_config: &ProjectConfig::default(),
+        toolchain: &toolchain,
+    };
+
+    let plan = plan_flow(context).unwrap();
+    assert_eq!(plan.target.as_deref(), Some("app"));
+    assert_eq!(plan.steps.len(), 4);
+}
+
+#[test]
+fn run_flow_runs_the_binary_and_passes_its_exit_code_through() {
+    let project = TempProject::new();
+    let binary = project.built_binary("app");
+    let mut runner = RecordingRunner::new()
+        .with_executables(vec![binary.clone()])
+        .with_binary_exit_code(7);
+    let project_config = ProjectConfig {
+        debug_run: true,
+        ..ProjectConfig::default()
+    };
+
+    let (code, reported) = project
+        .run(
+            &["--", "--verbose", "input.txt"],
+            &project_config,
+            &mut runner,
+        )
+        .unwrap();
+
+    assert_eq!(code, 7);
+    assert!(reported.is_empty());
+    assert_eq!(
+        runner.commands,
+        vec![
+            RecordedCommand::Cargo {
+                dir: project.path.clone(),
+                args: vec![
+                    "build".into(),
+                    "--message-format=json-render-diagnostics".into()
+                ],
+            },
+            RecordedCommand::Binary {
+                path: binary.path,
+                args: vec!["--verbose".into(), "input.txt".into()],
+            },
+        ]
+    );
+}
+
+#[test]
+fn run_flow_stops_when_the_build_fails() {
+    let project = TempProject::new();
+    let mut runner =
+        RecordingRunner::new().with_cargo_status(CommandStatus::Interrupted { signal: 2 });
+
+    let error = project
+        .run(&[], &ProjectConfig::default(), &mut runner)
+        .unwrap_err();
+
+    assert_eq!(error.exit_code(), 130);
+    assert_eq!(runner.commands.len(), 1);
+}
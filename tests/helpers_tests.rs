@@ -1,43 +1,14 @@
-use rustr::constants::CARGO_TOML;
-use rustr::helpers::get_binary_name;
-use std::fs;
-use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-struct TempProject {
-    path: PathBuf,
-}
-
-impl TempProject {
-    fn new(cargo_toml: &str) -> Self {
-        let unique = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_nanos();
-        let path = std::env::temp_dir().join(format!("rustr-helper-tests-{unique}"));
-        fs::create_dir_all(&path).unwrap();
-        fs::write(path.join(CARGO_TOML), cargo_toml).unwrap();
-        Self { path }
-    }
-
-    fn write_file(&self, relative_path: &str, contents: &str) {
-        let path = self.path.join(relative_path);
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).unwrap();
-        }
-        fs::write(path, contents).unwrap();
-    }
-}
+mod common;
 
-impl Drop for TempProject {
-    fn drop(&mut self) {
-        let _ = fs::remove_dir_all(&self.path);
-    }
-}
+use common::TempDir;
+use rustr::discovery::ProjectSearch;
+use rustr::error::RustrError;
+use rustr::helpers::{copy_bin, find_project_dir, get_binary_name, get_target};
+use std::fs;
 
 #[test]
 fn binary_name_prefers_package_name_over_first_explicit_bin() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "app"
@@ -59,7 +30,7 @@ path = "src/main.rs"
 
 #[test]
 fn binary_name_uses_default_run_when_present() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "app"
@@ -82,7 +53,7 @@ path = "src/bin/worker.rs"
 
 #[test]
 fn binary_name_uses_explicit_src_main_bin_name_when_renamed() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "sensa-cli"
@@ -101,7 +72,7 @@ path = "src/main.rs"
 
 #[test]
 fn binary_name_uses_package_name_when_src_main_exists() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "app"
@@ -124,7 +95,7 @@ path = "src/bin/helper-b.rs"
 
 #[test]
 fn binary_name_uses_single_explicit_bin_when_no_package_bin_exists() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "app"
@@ -143,7 +114,7 @@ path = "src/bin/worker.rs"
 
 #[test]
 fn binary_name_errors_on_ambiguous_explicit_bins() {
-    let project = TempProject::new(
+    let project = TempDir::with_manifest(
         r#"
 [package]
 name = "app"
@@ -163,3 +134,222 @@ path = "src/bin/worker-b.rs"
     let error = get_binary_name(&project.path).unwrap_err().to_string();
     assert!(error.contains("Multiple binary targets"));
 }
+
+#[test]
+fn binary_name_uses_single_src_bin_target() {
+    let project = TempDir::with_manifest("[package]\nname = \"app\"\nversion = \"0.1.0\"\n");
+    project.write_file("src/lib.rs", "");
+    project.write_file("src/bin/tool/main.rs", "fn main() {}");
+
+    assert_eq!(get_binary_name(&project.path).unwrap(), "tool");
+}
+
+#[test]
+fn binary_name_ignores_src_bin_without_autobins() {
+    let project = TempDir::with_manifest(
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nautobins = false\n",
+    );
+    project.write_file("src/bin/tool.rs", "fn main() {}");
+
+    assert_eq!(get_binary_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn binary_name_lists_ambiguous_src_bin_targets() {
+    let project = TempDir::with_manifest("[package]\nname = \"app\"\nversion = \"0.1.0\"\n");
+    project.write_file("src/bin/client.rs", "fn main() {}");
+    project.write_file("src/bin/server.rs", "fn main() {}");
+
+    let error = get_binary_name(&project.path).unwrap_err().to_string();
+    assert!(error.contains("Multiple binary targets"), "{error}");
+    assert!(error.contains("  client\n  server"), "{error}");
+}
+
+#[test]
+fn default_target_carries_required_features() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+version = "0.1.0"
+
+[[bin]]
+name = "app"
+path = "src/main.rs"
+required-features = ["cli"]
+"#,
+    );
+
+    let target = get_target(&project.path, None, None).unwrap();
+    assert_eq!(target.name, "app");
+    assert_eq!(target.required_features, vec!["cli"]);
+}
+
+fn package_manifest(name: &str) -> String {
+    format!("[package]\nname = \"{name}\"\nversion = \"0.1.0\"\n")
+}
+
+#[test]
+fn find_project_searches_roots_in_order() {
+    let first = TempDir::new();
+    let second = TempDir::new();
+    first.write_file("other/Cargo.toml", &package_manifest("other"));
+    second.write_file("billing/Cargo.toml", &package_manifest("billing"));
+    first.write_file("shared/Cargo.toml", &package_manifest("shared"));
+    second.write_file("shared/Cargo.toml", &package_manifest("shared"));
+
+    let search = ProjectSearch::new(vec![first.path.clone(), second.path.clone()], 1);
+
+    let billing = find_project_dir("billing", &search).unwrap();
+    assert_eq!(billing, second.path.join("billing"));
+
+    let shared = find_project_dir("shared", &search).unwrap();
+    assert_eq!(shared, first.path.join("shared"));
+}
+
+#[test]
+fn find_project_error_lists_every_searched_location() {
+    let first = TempDir::new();
+    let second = TempDir::new();
+    let search = ProjectSearch::new(vec![first.path.clone(), second.path.clone()], 3);
+
+    let error = find_project_dir("missing", &search)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Project directory not found: missing"));
+    assert!(error.contains(&first.path.display().to_string()));
+    assert!(error.contains(&second.path.display().to_string()));
+}
+
+#[test]
+fn find_project_discovers_nested_projects_by_package_name() {
+    let root = TempDir::new();
+    root.write_file(
+        "clients/acme/billing-service/Cargo.toml",
+        &package_manifest("billing"),
+    );
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let project = find_project_dir("billing", &search).unwrap();
+    assert_eq!(project, root.path.join("clients/acme/billing-service"));
+}
+
+#[test]
+fn find_project_respects_search_depth() {
+    let root = TempDir::new();
+    root.write_file(
+        "clients/acme/billing/Cargo.toml",
+        &package_manifest("billing"),
+    );
+    let search = ProjectSearch::new(vec![root.path.clone()], 2);
+
+    let error = find_project_dir("billing", &search)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Project directory not found"));
+}
+
+#[test]
+fn find_project_skips_target_and_git_dirs() {
+    let root = TempDir::new();
+    root.write_file(
+        "target/package/billing/Cargo.toml",
+        &package_manifest("billing"),
+    );
+    root.write_file(".git/billing/Cargo.toml", &package_manifest("billing"));
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let error = find_project_dir("billing", &search)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Project directory not found"));
+}
+
+#[test]
+fn find_project_prefers_package_name_over_directory_name() {
+    let root = TempDir::new();
+    root.write_file("billing/Cargo.toml", &package_manifest("billing-legacy"));
+    root.write_file("services/api/Cargo.toml", &package_manifest("billing"));
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let project = find_project_dir("billing", &search).unwrap();
+    assert_eq!(project, root.path.join("services/api"));
+}
+
+#[test]
+fn find_project_reports_ambiguous_package_names() {
+    let root = TempDir::new();
+    root.write_file("clients/acme/Cargo.toml", &package_manifest("billing"));
+    root.write_file("clients/globex/Cargo.toml", &package_manifest("billing"));
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let error = find_project_dir("billing", &search)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("ambiguous"), "{error}");
+    assert!(error.contains(&root.path.join("clients/acme").display().to_string()));
+    assert!(error.contains(&root.path.join("clients/globex").display().to_string()));
+}
+
+#[test]
+fn find_project_error_suggests_close_names() {
+    let root = TempDir::new();
+    root.write_file("billing-api/Cargo.toml", &package_manifest("billing-api"));
+    root.write_file("invoices/Cargo.toml", &package_manifest("invoices"));
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let error = find_project_dir("biling-api", &search)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Did you mean: billing-api?"), "{error}");
+}
+
+#[test]
+fn find_project_prefix_requires_opt_in() {
+    let root = TempDir::new();
+    root.write_file("billing-api/Cargo.toml", &package_manifest("billing-api"));
+    let search = ProjectSearch::new(vec![root.path.clone()], 3);
+
+    let error = find_project_dir("bil", &search).unwrap_err().to_string();
+    assert!(error.contains("Did you mean: billing-api?"), "{error}");
+
+    let project = find_project_dir("bil", &search.with_prefix_match(true)).unwrap();
+    assert_eq!(project, root.path.join("billing-api"));
+}
+
+#[test]
+fn find_project_rejects_ambiguous_prefix() {
+    let root = TempDir::new();
+    root.write_file("billing-api/Cargo.toml", &package_manifest("billing-api"));
+    root.write_file(
+        "billing-worker/Cargo.toml",
+        &package_manifest("billing-worker"),
+    );
+    let search = ProjectSearch::new(vec![root.path.clone()], 3).with_prefix_match(true);
+
+    let error = find_project_dir("bil", &search).unwrap_err().to_string();
+    assert!(error.contains("ambiguous"), "{error}");
+}
+
+#[test]
+fn copy_bin_creates_the_destination_and_copies() {
+    let project = TempDir::new();
+    project.write_file("target/release/app", "binary");
+    let dest_dir = project.path.join("installed/bin");
+
+    let copied = copy_bin(&project.path.join("target/release/app"), &dest_dir).unwrap();
+
+    assert_eq!(copied, dest_dir.join("app"));
+    assert_eq!(fs::read_to_string(copied).unwrap(), "binary");
+}
+
+#[test]
+fn copy_bin_reports_a_missing_binary() {
+    let project = TempDir::new();
+    let missing = project.path.join("target/release/app");
+
+    let error = copy_bin(&missing, &project.path.join("installed")).unwrap_err();
+
+    assert!(matches!(&error, RustrError::BinaryMissing { path } if *path == missing));
+    assert!(error.to_string().starts_with("Binary not found"));
+}
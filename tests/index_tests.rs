@@ -0,0 +1,136 @@
+mod common;
+
+use common::TempDir;
+use rustr::constants::CARGO_TOML;
+use rustr::discovery::ProjectSearch;
+use rustr::index::ProjectIndex;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn search_with_index(root: &TempDir, cache: &TempDir) -> ProjectSearch {
+    ProjectSearch::new(vec![root.path.clone()], 3)
+        .with_index_file(Some(cache.path.join("index.json")))
+}
+
+fn set_manifest_mtime(dir: &Path, mtime: SystemTime) {
+    File::options()
+        .write(true)
+        .open(dir.join(CARGO_TOML))
+        .unwrap()
+        .set_modified(mtime)
+        .unwrap();
+}
+
+fn set_dir_mtime(dir: &Path, mtime: SystemTime) {
+    File::open(dir).unwrap().set_modified(mtime).unwrap();
+}
+
+#[test]
+fn index_records_package_and_binary_names() {
+    let root = TempDir::new();
+    let dir = root.write_package("services/billing", "billing-api");
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+
+    let index = ProjectIndex::build(&ProjectSearch::new(vec![root.path.clone()], 3));
+
+    assert_eq!(index.projects.len(), 1);
+    let project = &index.projects[0];
+    assert_eq!(project.package_name.as_deref(), Some("billing-api"));
+    assert_eq!(project.binary_name.as_deref(), Some("billing-api"));
+    assert_eq!(project.dir, dir);
+    assert!(project.is_fresh());
+}
+
+#[test]
+fn index_round_trips_through_cache_file() {
+    let root = TempDir::new();
+    let cache = TempDir::new();
+    root.write_package("billing", "billing");
+    let search = search_with_index(&root, &cache);
+
+    let index = ProjectIndex::rebuild(&search);
+    let loaded = ProjectIndex::load_for(&search).unwrap();
+    assert_eq!(loaded, index);
+}
+
+#[test]
+fn index_is_ignored_when_search_roots_change() {
+    let root = TempDir::new();
+    let other = TempDir::new();
+    let cache = TempDir::new();
+    root.write_package("billing", "billing");
+    let search = search_with_index(&root, &cache);
+    ProjectIndex::rebuild(&search);
+
+    let moved =
+        ProjectSearch::new(vec![other.path.clone()], 3).with_index_file(search.index_file.clone());
+    assert!(ProjectIndex::load_for(&moved).is_none());
+
+    let deeper =
+        ProjectSearch::new(search.roots.clone(), 5).with_index_file(search.index_file.clone());
+    assert!(ProjectIndex::load_for(&deeper).is_none());
+}
+
+#[test]
+fn lookup_miss_rebuilds_index() {
+    let root = TempDir::new();
+    let cache = TempDir::new();
+    root.write_package("billing", "billing");
+    let search = search_with_index(&root, &cache);
+    ProjectIndex::rebuild(&search);
+
+    let dir = root.write_package("clients/acme", "acme");
+    let matches = search.find("acme");
+
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].dir, dir);
+    let cached = ProjectIndex::load_for(&search).unwrap();
+    assert_eq!(cached.projects.len(), 2);
+}
+
+#[test]
+fn changed_manifest_invalidates_cached_entry() {
+    let root = TempDir::new();
+    let cache = TempDir::new();
+    let dir = root.write_package("billing", "billing");
+    set_manifest_mtime(&dir, UNIX_EPOCH + Duration::from_secs(1_000_000));
+    let search = search_with_index(&root, &cache);
+    ProjectIndex::rebuild(&search);
+
+    root.write_package("billing", "invoices");
+    set_manifest_mtime(&dir, UNIX_EPOCH + Duration::from_secs(2_000_000));
+
+    assert!(!ProjectIndex::load_for(&search).unwrap().projects[0].is_fresh());
+    // The directory name still matches, so the lookup refreshes the entry
+    let matches = search.find("billing");
+    assert_eq!(matches.len(), 1);
+    assert_eq!(matches[0].package_name.as_deref(), Some("invoices"));
+}
+
+#[test]
+fn new_src_bin_binary_invalidates_cached_entry() {
+    let root = TempDir::new();
+    let cache = TempDir::new();
+    let dir = root.write_package("app", "app");
+    root.write_file("app/src/bin/worker.rs", "fn main() {}");
+    set_dir_mtime(
+        &dir.join("src/bin"),
+        UNIX_EPOCH + Duration::from_secs(1_000_000),
+    );
+    let search = search_with_index(&root, &cache);
+    let index = ProjectIndex::rebuild(&search);
+    assert_eq!(index.projects[0].binary_name.as_deref(), Some("worker"));
+
+    root.write_file("app/src/bin/server.rs", "fn main() {}");
+    set_dir_mtime(
+        &dir.join("src/bin"),
+        UNIX_EPOCH + Duration::from_secs(2_000_000),
+    );
+
+    assert!(!ProjectIndex::load_for(&search).unwrap().projects[0].is_fresh());
+    // Two binaries and nothing to pick one, so there's no binary name any more
+    let matches = search.find("app");
+    assert_eq!(matches[0].binary_name, None);
+}
@@ -0,0 +1,130 @@
+mod common;
+
+use common::TempDir;
+use rustr::artifacts::resolve_target_dir_from;
+use rustr::constants::{BINARY_EXTENSION, CARGO_TOML};
+use rustr::discovery::ProjectSearch;
+use rustr::list::{ProjectListing, format_age, format_table, list_projects_with};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Lists the projects under `root`, with target dirs resolved as if no
+/// `CARGO_TARGET_DIR` or `$CARGO_HOME` config were set.
+fn list(root: &TempDir) -> Vec<ProjectListing> {
+    list_projects_with(
+        &ProjectSearch::new(vec![root.path.clone()], 3),
+        &|cargo_dir: &Path| resolve_target_dir_from(cargo_dir, None, None),
+    )
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[test]
+fn list_reports_binaries_and_release_builds() {
+    let root = TempDir::new();
+    root.write_file(
+        &format!("billing/{CARGO_TOML}"),
+        "[package]\nname = \"billing-api\"\n\n[[bin]]\nname = \"billing\"\npath = \"src/main.rs\"\n",
+    );
+    root.write_file(
+        &format!("billing/target/release/billing{BINARY_EXTENSION}"),
+        "",
+    );
+    root.write_file(
+        &format!("invoices/{CARGO_TOML}"),
+        "[package]\nname = \"invoices\"\n",
+    );
+    root.write_file("invoices/src/main.rs", "fn main() {}");
+
+    let listings = list(&root);
+    assert_eq!(listings.len(), 2);
+
+    let billing = &listings[0];
+    assert_eq!(billing.name, "billing-api");
+    assert_eq!(billing.path, root.path.join("billing"));
+    assert_eq!(billing.binary.as_deref(), Some("billing"));
+    assert_eq!(
+        billing.release_binary,
+        Some(
+            root.path
+                .join(format!("billing/target/release/billing{BINARY_EXTENSION}"))
+        )
+    );
+    assert!(billing.last_build.is_some_and(|built| built <= now_secs()));
+
+    let invoices = &listings[1];
+    assert_eq!(invoices.name, "invoices");
+    assert_eq!(invoices.binary.as_deref(), Some("invoices"));
+    assert_eq!(invoices.release_binary, None);
+    assert_eq!(invoices.last_build, None);
+}
+
+#[test]
+fn list_looks_for_release_builds_in_the_overridden_target_dir() {
+    let root = TempDir::new();
+    root.write_file(
+        &format!("billing/{CARGO_TOML}"),
+        "[package]\nname = \"billing\"\n",
+    );
+    root.write_file(&format!("shared/release/billing{BINARY_EXTENSION}"), "");
+    let shared = root.path.join("shared");
+
+    let listings = list_projects_with(
+        &ProjectSearch::new(vec![root.path.clone()], 3),
+        &|cargo_dir: &Path| resolve_target_dir_from(cargo_dir, Some(shared.clone()), None),
+    );
+
+    assert_eq!(
+        listings[0].release_binary,
+        Some(shared.join(format!("release/billing{BINARY_EXTENSION}")))
+    );
+}
+
+#[test]
+fn list_serializes_to_json() {
+    let root = TempDir::new();
+    root.write_file(
+        &format!("billing/{CARGO_TOML}"),
+        "[package]\nname = \"billing\"\n",
+    );
+
+    let listings = list(&root);
+    let json = serde_json::to_value(&listings).unwrap();
+
+    assert_eq!(json[0]["name"], "billing");
+    assert_eq!(json[0]["binary"], "billing");
+    assert!(json[0]["release_binary"].is_null());
+    assert!(json[0]["last_build"].is_null());
+}
+
+#[test]
+fn table_has_a_row_per_project() {
+    let root = TempDir::new();
+    root.write_file(
+        &format!("billing/{CARGO_TOML}"),
+        "[package]\nname = \"billing\"\n",
+    );
+
+    let table = format_table(&list(&root));
+    let lines: Vec<_> = table.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("LAST BUILD"));
+    assert!(lines[1].starts_with("billing"));
+    assert!(lines[1].ends_with("no       -"), "{}", lines[1]);
+    assert_eq!(format_table(&[]), "No projects found");
+}
+
+#[test]
+fn format_age_uses_coarse_units() {
+    let now = now_secs();
+    assert_eq!(format_age(now), "just now");
+    assert_eq!(format_age(now - 5 * 60), "5m ago");
+    assert_eq!(format_age(now - 3 * 3_600), "3h ago");
+    assert_eq!(format_age(now - 2 * 86_400), "2d ago");
+}
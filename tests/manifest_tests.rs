@@ -0,0 +1,137 @@
+mod common;
+
+use common::TempDir;
+use rustr::helpers::{get_binary_name, get_package_name, is_self_project};
+use rustr::manifest::{BinTarget, Manifest};
+
+#[test]
+fn manifest_parses_package_and_bins() {
+    let manifest = Manifest::parse(
+        r#"
+[package]
+name = "app"
+default-run = "worker"
+autobins = false
+
+[[bin]]
+name = "worker"
+path = "src/bin/worker.rs"
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(manifest.package_name(), Some("app"));
+    assert_eq!(manifest.default_run(), Some("worker"));
+    assert!(!manifest.autobins());
+    assert_eq!(
+        manifest.bin,
+        vec![BinTarget {
+            name: Some("worker".into()),
+            path: Some("src/bin/worker.rs".into()),
+            required_features: vec![],
+        }]
+    );
+}
+
+#[test]
+fn manifest_without_package_has_no_name() {
+    let manifest = Manifest::parse("[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+    assert_eq!(manifest.package_name(), None);
+    assert!(manifest.autobins());
+}
+
+#[test]
+fn manifest_reports_invalid_toml() {
+    let project = TempDir::with_manifest("[package\nname = \"app\"\n");
+    let error = format!("{:#}", Manifest::load(&project.path).unwrap_err());
+    assert!(error.contains("Invalid"), "{error}");
+}
+
+#[test]
+fn package_name_with_single_quoted_literal() {
+    let project = TempDir::with_manifest("[package]\nname = 'app'\n");
+    assert_eq!(get_package_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn package_header_followed_by_comment() {
+    let project = TempDir::with_manifest("[package] # the main crate\nname = \"app\"\n");
+    assert_eq!(get_package_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn package_header_with_spaces() {
+    let project = TempDir::with_manifest("[ package ]\nname = \"app\"\n");
+    assert_eq!(get_package_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn package_name_after_multiline_string() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+description = """
+name = "decoy"
+"""
+name = "app"
+"#,
+    );
+    assert_eq!(get_package_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn package_as_inline_table() {
+    let project = TempDir::with_manifest("package = { name = \"app\", version = \"0.1.0\" }\n");
+    assert_eq!(get_package_name(&project.path).unwrap(), "app");
+}
+
+#[test]
+fn binary_name_from_inline_bin_table() {
+    let project = TempDir::with_manifest(
+        r#"
+bin = [{ name = "tool", path = "src/main.rs" }]
+
+[package]
+name = "app"
+"#,
+    );
+    assert_eq!(get_binary_name(&project.path).unwrap(), "tool");
+}
+
+#[test]
+fn default_run_with_literal_string_and_trailing_comment() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+default-run = 'worker' # picked by cargo run
+
+[[bin]]
+name = "helper"
+path = "src/bin/helper.rs"
+
+[[bin]]
+name = "worker"
+path = "src/bin/worker.rs"
+"#,
+    );
+    assert_eq!(get_binary_name(&project.path).unwrap(), "worker");
+}
+
+#[test]
+fn self_project_only_checks_package_name() {
+    let other = TempDir::with_manifest(
+        r#"
+[[bin]]
+name = "rustr"
+path = "src/main.rs"
+
+[package]
+name = "other"
+"#,
+    );
+    assert!(!is_self_project(&other.path).unwrap());
+
+    let own = TempDir::with_manifest("[ package ]\nname = 'rustr'\n");
+    assert!(is_self_project(&own.path).unwrap());
+}
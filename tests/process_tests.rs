@@ -0,0 +1,131 @@
+#![cfg(unix)]
+
+use rustr::process::{
+    CargoError, CargoFailure, CommandStatus, SignalForwarder, classify_exit, exit_code,
+    spawn_in_own_group,
+};
+use std::path::Path;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[test]
+fn exit_code_passes_through_the_process_code() {
+    let status = Command::new("sh").args(["-c", "exit 3"]).status().unwrap();
+    assert_eq!(exit_code(status), 3);
+}
+
+#[test]
+fn exit_code_for_a_signal_is_128_plus_the_signal() {
+    let status = Command::new("sh")
+        .args(["-c", "kill -TERM $$"])
+        .status()
+        .unwrap();
+    assert_eq!(exit_code(status), 128 + 15);
+}
+
+#[test]
+fn classify_exit_tells_failures_apart() {
+    let status = Command::new("sh").args(["-c", "exit 0"]).status().unwrap();
+    assert_eq!(classify_exit(status, None), CommandStatus::Success);
+
+    let status = Command::new("sh")
+        .args(["-c", "exit 101"])
+        .status()
+        .unwrap();
+    assert_eq!(
+        classify_exit(status, None),
+        CommandStatus::Failed { code: Some(101) }
+    );
+    assert_eq!(
+        classify_exit(status, Some(2)),
+        CommandStatus::Interrupted { signal: 2 }
+    );
+
+    let status = Command::new("sh")
+        .args(["-c", "kill -KILL $$"])
+        .status()
+        .unwrap();
+    assert_eq!(
+        classify_exit(status, None),
+        CommandStatus::Signaled { signal: 9 }
+    );
+}
+
+#[test]
+fn command_status_becomes_the_matching_failure() {
+    let build_failed = |code| CargoFailure::BuildFailed { code };
+
+    assert_eq!(CommandStatus::Success.failure(build_failed), None);
+    assert_eq!(
+        CommandStatus::Failed { code: Some(101) }.failure(build_failed),
+        Some(CargoFailure::BuildFailed { code: Some(101) })
+    );
+    assert_eq!(
+        CommandStatus::Signaled { signal: 9 }.failure(build_failed),
+        Some(CargoFailure::Signaled { signal: 9 })
+    );
+    assert_eq!(
+        CommandStatus::Interrupted { signal: 2 }.failure(build_failed),
+        Some(CargoFailure::Interrupted { signal: 2 })
+    );
+}
+
+#[test]
+fn cargo_errors_map_to_distinct_exit_codes() {
+    let error = |failure| CargoError::new(Path::new("/work/app"), &["test", "--release"], failure);
+
+    let tests_failed = error(CargoFailure::TestsFailed { code: Some(101) });
+    assert_eq!(tests_failed.command_line, "cargo test --release");
+    assert_eq!(
+        tests_failed.to_string(),
+        "Tests failed: 'cargo test --release' exited with code 101 (in /work/app)"
+    );
+    assert_eq!(tests_failed.exit_code(), 4);
+
+    assert_eq!(
+        error(CargoFailure::BuildFailed { code: Some(101) }).exit_code(),
+        3
+    );
+    assert_eq!(error(CargoFailure::NotFound).exit_code(), 127);
+    assert_eq!(error(CargoFailure::Signaled { signal: 9 }).exit_code(), 137);
+    assert_eq!(
+        error(CargoFailure::Interrupted { signal: 2 }).to_string(),
+        "'cargo test --release' was interrupted by SIGINT (in /work/app)"
+    );
+}
+
+// Signal handling is process-wide, so every forwarding scenario lives in one
+// test to keep them from running concurrently.
+#[test]
+fn forwarder_signals_the_child_group_and_kills_it_after_the_grace_period() {
+    let signal_self = |signal: &str| {
+        Command::new("kill")
+            .args([signal, &std::process::id().to_string()])
+            .status()
+            .unwrap();
+    };
+
+    // A child that exits on SIGINT gets the forwarded signal
+    let mut command = Command::new("sleep");
+    command.arg("10");
+    let mut child = spawn_in_own_group(&mut command).unwrap();
+    let forwarder = SignalForwarder::start(&child, Duration::from_secs(10));
+    signal_self("-INT");
+    let status = child.wait().unwrap();
+    assert_eq!(forwarder.finish(), Some(2));
+    assert_eq!(exit_code(status), 128 + 2);
+
+    // A child that ignores SIGTERM is killed once the grace period is over
+    let mut command = Command::new("sh");
+    command.args(["-c", "trap '' TERM; sleep 10 & wait"]);
+    let mut child = spawn_in_own_group(&mut command).unwrap();
+    let forwarder = SignalForwarder::start(&child, Duration::from_millis(200));
+    let started = Instant::now();
+    thread::sleep(Duration::from_millis(100));
+    signal_self("-TERM");
+    let status = child.wait().unwrap();
+    assert_eq!(forwarder.finish(), Some(15));
+    assert_eq!(exit_code(status), 128 + 9);
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::TempDir;
+use rustr::artifacts::resolve_target_dir_from;
+use rustr::constants::CARGO_TOML;
+use rustr::discovery::ProjectSearch;
+use rustr::helpers::locate_project;
+use rustr::project::{Project, find_workspace_root};
+use std::ffi::OsString;
+
+fn os_args(args: &[&str]) -> Vec<OsString> {
+    args.iter().map(OsString::from).collect()
+}
+
+fn workspace_fixture() -> TempDir {
+    let dir = TempDir::new();
+    dir.write_file(
+        &format!("shop/{CARGO_TOML}"),
+        r#"
+[workspace]
+members = ["crates/*", "tools/cli"]
+exclude = ["crates/experimental"]
+"#,
+    );
+    dir.write_package("shop/crates/billing", "billing");
+    dir.write_package("shop/crates/inventory", "inventory");
+    dir.write_package("shop/crates/experimental", "experimental");
+    dir.write_package("shop/tools/cli", "shop-cli");
+    dir
+}
+
+#[test]
+fn standalone_package_builds_in_place() {
+    let dir = TempDir::new();
+    dir.write_package("app", "app");
+
+    let project = Project::resolve(&dir.path.join("app"), None).unwrap();
+    assert_eq!(project.package_name, "app");
+    assert_eq!(project.workspace_root, None);
+    assert_eq!(project.cargo_dir(), dir.path.join("app"));
+    assert_eq!(
+        resolve_target_dir_from(project.cargo_dir(), None, None),
+        dir.path.join("app/target")
+    );
+    assert_eq!(project.cargo_args(&["build"]), os_args(&["build"]));
+}
+
+#[test]
+fn member_package_uses_workspace_root_and_target_dir() {
+    let dir = workspace_fixture();
+    let root = dir.path.join("shop");
+
+    let project = Project::resolve(&root.join("crates/billing"), None).unwrap();
+    assert_eq!(project.package_name, "billing");
+    assert_eq!(project.workspace_root, Some(root.clone()));
+    assert_eq!(project.cargo_dir(), root);
+    assert_eq!(
+        resolve_target_dir_from(project.cargo_dir(), None, None),
+        root.join("target")
+    );
+    assert_eq!(
+        project.cargo_args(&["build", "--release"]),
+        os_args(&["build", "--release", "-p", "billing"])
+    );
+}
+
+#[test]
+fn excluded_package_is_standalone() {
+    let dir = workspace_fixture();
+    let excluded = dir.path.join("shop/crates/experimental");
+
+    assert_eq!(find_workspace_root(&excluded), None);
+    let project = Project::resolve(&excluded, None).unwrap();
+    assert_eq!(project.cargo_dir(), excluded);
+}
+
+#[test]
+fn workspace_root_resolves_requested_member() {
+    let dir = workspace_fixture();
+    let root = dir.path.join("shop");
+
+    let project = Project::resolve(&root, Some("shop-cli")).unwrap();
+    assert_eq!(project.dir, root.join("tools/cli"));
+    assert_eq!(project.workspace_root, Some(root));
+}
+
+#[test]
+fn virtual_workspace_with_several_members_is_ambiguous() {
+    let dir = workspace_fixture();
+
+    let error = Project::resolve(&dir.path.join("shop"), Some("shop"))
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Choose a member package"), "{error}");
+    assert!(error.contains("billing"));
+    assert!(error.contains("shop-cli"));
+    assert!(!error.contains("experimental"));
+}
+
+#[test]
+fn virtual_workspace_uses_single_default_member() {
+    let dir = TempDir::new();
+    dir.write_file(
+        &format!("shop/{CARGO_TOML}"),
+        "[workspace]\nmembers = [\"crates/*\"]\ndefault-members = [\"crates/billing\"]\n",
+    );
+    dir.write_package("shop/crates/billing", "billing");
+    dir.write_package("shop/crates/inventory", "inventory");
+
+    let project = Project::resolve(&dir.path.join("shop"), None).unwrap();
+    assert_eq!(project.package_name, "billing");
+}
+
+#[test]
+fn workspace_root_package_is_selected_over_members() {
+    let dir = TempDir::new();
+    dir.write_file(
+        &format!("app/{CARGO_TOML}"),
+        "[package]\nname = \"app\"\n\n[workspace]\nmembers = [\"plugins/*\"]\n",
+    );
+    dir.write_package("app/plugins/extra", "extra");
+
+    let project = Project::resolve(&dir.path.join("app"), Some("app")).unwrap();
+    assert_eq!(project.dir, dir.path.join("app"));
+    assert_eq!(
+        project.cargo_args(&["test"]),
+        os_args(&["test", "-p", "app"])
+    );
+}
+
+#[test]
+fn locate_project_finds_members_beyond_search_depth() {
+    let dir = workspace_fixture();
+    let search = ProjectSearch::new(vec![dir.path.clone()], 1);
+
+    let project = locate_project("inventory", &search).unwrap();
+    assert_eq!(project.dir, dir.path.join("shop/crates/inventory"));
+    assert_eq!(project.workspace_root, Some(dir.path.join("shop")));
+}
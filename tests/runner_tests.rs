@@ -0,0 +1,59 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::TempDir;
+use rustr::process::CommandStatus;
+use rustr::runner::{CommandRunner, SystemRunner};
+use std::ffi::OsString;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+// A cargo stand-in that says it has started, then builds for a long time
+// without writing anything
+const FAKE_CARGO: &str = "#!/bin/sh\ntouch started\nexec sleep 30\n";
+
+// Puts a fake cargo on PATH and signals this process, so it must stay the
+// only test in this file.
+#[test]
+fn cargo_build_forwards_signals_while_reading_build_output() {
+    let dir = TempDir::new();
+    dir.write_file("bin/cargo", FAKE_CARGO);
+    let fake_cargo = dir.path.join("bin/cargo");
+    fs::set_permissions(&fake_cargo, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = std::env::var_os("PATH").unwrap_or_default();
+    let mut paths = vec![dir.path.join("bin")];
+    paths.extend(std::env::split_paths(&path));
+    // SAFETY: nothing else in this test binary reads the environment
+    unsafe { std::env::set_var("PATH", std::env::join_paths(paths).unwrap()) };
+
+    let started_file = dir.path.join("started");
+    let signaller = {
+        let started_file = started_file.clone();
+        thread::spawn(move || {
+            let started = Instant::now();
+            while !started_file.exists() && started.elapsed() < Duration::from_secs(10) {
+                thread::sleep(Duration::from_millis(20));
+            }
+            thread::sleep(Duration::from_millis(200));
+            Command::new("kill")
+                .args(["-TERM", &std::process::id().to_string()])
+                .status()
+                .unwrap();
+        })
+    };
+
+    let mut runner = SystemRunner::new(Duration::from_secs(5), |line| println!("{}", line));
+    let started = Instant::now();
+    let (status, executables) = runner
+        .cargo_build(&dir.path, &[OsString::from("build")])
+        .unwrap();
+    signaller.join().unwrap();
+
+    assert_eq!(status, CommandStatus::Interrupted { signal: 15 });
+    assert!(executables.is_empty());
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
@@ -0,0 +1,39 @@
+use rustr::suggest::{closest_matches, did_you_mean, edit_distance};
+
+#[test]
+fn edit_distance_counts_single_edits() {
+    assert_eq!(edit_distance("billing", "billing"), 0);
+    assert_eq!(edit_distance("biling", "billing"), 1);
+    assert_eq!(edit_distance("billign", "billing"), 2);
+    assert_eq!(edit_distance("", "abc"), 3);
+    assert_eq!(edit_distance("项目", "项目名"), 1);
+}
+
+#[test]
+fn closest_matches_rank_prefix_then_substring_then_distance() {
+    let candidates = ["api-billing", "billing-api", "bolling", "unrelated"];
+    let matches = closest_matches("billing", candidates);
+    assert_eq!(matches, vec!["billing-api", "api-billing", "bolling"]);
+}
+
+#[test]
+fn closest_matches_ignore_distant_names() {
+    let matches = closest_matches("xyz", ["billing", "invoices"]);
+    assert!(matches.is_empty());
+}
+
+#[test]
+fn closest_matches_are_limited_and_deduplicated() {
+    let candidates = ["app-a", "app-b", "app-a", "app-c", "app-d"];
+    let matches = closest_matches("app", candidates);
+    assert_eq!(matches, vec!["app-a", "app-b", "app-c"]);
+}
+
+#[test]
+fn did_you_mean_formats_hint() {
+    assert_eq!(did_you_mean(&[]), "");
+    assert_eq!(
+        did_you_mean(&["billing", "billing-api"]),
+        "\nDid you mean: billing, billing-api?"
+    );
+}
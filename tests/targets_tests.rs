@@ -0,0 +1,151 @@
+mod common;
+
+use common::TempDir;
+use rustr::manifest::Manifest;
+use rustr::targets::{TargetKind, discover_targets, find_target, select_target};
+
+fn touch(project: &TempDir, relative_path: &str) {
+    project.write_file(relative_path, "fn main() {}\n");
+}
+
+fn target_names(project: &TempDir, kind: TargetKind) -> Vec<String> {
+    let manifest = Manifest::load(&project.path).unwrap();
+    discover_targets(&project.path, &manifest, kind)
+        .into_iter()
+        .map(|target| target.name)
+        .collect()
+}
+
+#[test]
+fn discovers_main_and_src_bin_targets() {
+    let project = TempDir::with_manifest("[package]\nname = \"app\"\n");
+    touch(&project, "src/main.rs");
+    touch(&project, "src/bin/worker.rs");
+    touch(&project, "src/bin/server/main.rs");
+    touch(&project, "src/bin/notes.txt");
+
+    assert_eq!(
+        target_names(&project, TargetKind::Bin),
+        vec!["app", "server", "worker"]
+    );
+}
+
+#[test]
+fn explicit_bins_replace_inferred_targets_with_same_path() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+
+[[bin]]
+name = "renamed"
+path = "./src/bin/worker.rs"
+"#,
+    );
+    touch(&project, "src/bin/worker.rs");
+    touch(&project, "src/bin/other.rs");
+
+    assert_eq!(
+        target_names(&project, TargetKind::Bin),
+        vec!["renamed", "other"]
+    );
+}
+
+#[test]
+fn autobins_false_keeps_only_explicit_bins() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+autobins = false
+
+[[bin]]
+name = "tool"
+path = "tools/tool.rs"
+"#,
+    );
+    touch(&project, "src/main.rs");
+    touch(&project, "src/bin/worker.rs");
+
+    assert_eq!(target_names(&project, TargetKind::Bin), vec!["tool"]);
+}
+
+#[test]
+fn discovers_examples() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+
+[[example]]
+name = "custom"
+path = "demos/custom.rs"
+"#,
+    );
+    touch(&project, "examples/basic.rs");
+    touch(&project, "examples/multi/main.rs");
+
+    assert_eq!(
+        target_names(&project, TargetKind::Example),
+        vec!["custom", "basic", "multi"]
+    );
+}
+
+#[test]
+fn select_target_lists_available_targets() {
+    let project = TempDir::with_manifest("[package]\nname = \"app\"\n");
+    touch(&project, "src/main.rs");
+    touch(&project, "src/bin/worker.rs");
+
+    let target = select_target(&project.path, TargetKind::Bin, "worker").unwrap();
+    assert_eq!(target.name, "worker");
+    assert_eq!(target.kind, TargetKind::Bin);
+
+    let err = select_target(&project.path, TargetKind::Bin, "wroker")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("No binary target named 'wroker'"), "{err}");
+    assert!(err.contains("Available binaries: app, worker"), "{err}");
+    assert!(err.contains("Did you mean: worker?"), "{err}");
+
+    let err = select_target(&project.path, TargetKind::Example, "demo")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("Available examples: (none)"), "{err}");
+}
+
+#[test]
+fn targets_record_required_features() {
+    let project = TempDir::with_manifest(
+        r#"
+[package]
+name = "app"
+
+[features]
+cli = []
+extra = []
+
+[[bin]]
+name = "tool"
+path = "src/bin/tool.rs"
+required-features = ["cli", "extra"]
+"#,
+    );
+    touch(&project, "src/main.rs");
+    touch(&project, "src/bin/tool.rs");
+
+    let tool = select_target(&project.path, TargetKind::Bin, "tool").unwrap();
+    assert_eq!(tool.required_features, vec!["cli", "extra"]);
+    assert_eq!(
+        tool.required_feature_args(),
+        vec!["--features", "cli,extra"]
+    );
+    assert_eq!(
+        tool.to_string(),
+        "tool (enabling required features: cli, extra)"
+    );
+
+    let app = find_target(&project.path, TargetKind::Bin, "app");
+    assert!(app.required_feature_args().is_empty());
+    assert_eq!(app.to_string(), "app");
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::TempDir;
+use rustr::toolchain::{Toolchain, ToolchainSource};
+use std::fs;
+
+#[test]
+fn explicit_toolchain_wins_and_is_forwarded() {
+    let dir = TempDir::new();
+    dir.write_file("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    let toolchain = Toolchain::resolve_from(Some("nightly"), Some("beta".into()), &dir.path);
+    assert_eq!(toolchain.name, Some("nightly".into()));
+    assert_eq!(toolchain.source, ToolchainSource::Argument);
+    assert_eq!(toolchain.cargo_arg(), Some("+nightly".into()));
+    assert_eq!(toolchain.to_string(), "nightly (from +nightly)");
+}
+
+#[test]
+fn environment_toolchain_beats_files() {
+    let dir = TempDir::new();
+    dir.write_file("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    let toolchain = Toolchain::resolve_from(None, Some("beta".into()), &dir.path);
+    assert_eq!(toolchain.source, ToolchainSource::Environment);
+    assert_eq!(toolchain.cargo_arg(), None);
+    assert_eq!(toolchain.to_string(), "beta (from RUSTUP_TOOLCHAIN)");
+}
+
+#[test]
+fn toolchain_file_is_found_above_the_project() {
+    let dir = TempDir::new();
+    dir.write_file(
+        "rust-toolchain.toml",
+        "[toolchain]\nchannel = \"1.80.0\"\ncomponents = [\"clippy\"]\n",
+    );
+    fs::create_dir_all(dir.path.join("crates/app")).unwrap();
+
+    let toolchain = Toolchain::resolve_from(None, None, &dir.path.join("crates/app"));
+    assert_eq!(toolchain.name, Some("1.80.0".into()));
+    assert_eq!(
+        toolchain.source,
+        ToolchainSource::File(dir.path.join("rust-toolchain.toml"))
+    );
+    assert_eq!(toolchain.cargo_arg(), None);
+}
+
+#[test]
+fn legacy_toolchain_file_holds_a_channel_name() {
+    let dir = TempDir::new();
+    dir.write_file("rust-toolchain", "nightly-2024-05-01\n");
+    dir.write_file("rust-toolchain.toml", "[toolchain]\nchannel = \"stable\"\n");
+
+    let toolchain = Toolchain::resolve_from(None, None, &dir.path);
+    assert_eq!(toolchain.name, Some("nightly-2024-05-01".into()));
+    assert_eq!(
+        toolchain.source,
+        ToolchainSource::File(dir.path.join("rust-toolchain"))
+    );
+}
+
+#[test]
+fn no_override_uses_the_default_toolchain() {
+    let dir = TempDir::new();
+
+    let toolchain = Toolchain::resolve_from(None, None, &dir.path);
+    assert_eq!(toolchain.source, ToolchainSource::Default);
+    assert_eq!(toolchain.to_string(), "default");
+}